@@ -33,9 +33,12 @@ use camino_tempfile::{NamedUtf8TempFile, Utf8TempDir};
 use predicates::{
     path::PredicateFileContentExt, reflection::PredicateReflection, str::PredicateStrExt,
 };
-use predicates_core::Predicate;
+use predicates_core::{
+    Predicate,
+    reflection::{Case, Product},
+};
 use predicates_tree::CaseTreeExt;
-use std::{fmt, path::Path};
+use std::{fmt, fs, io, path::Path};
 
 /// Assert the state of files within a [`Utf8TempDir`].
 ///
@@ -544,6 +547,242 @@ where
     }
 }
 
+/// Predicate on the set of paths within a directory.
+///
+/// Each pattern is a path relative to the directory, or a glob matched against
+/// such paths. Paths use `/` as a separator on all platforms, and in globs, `*`
+/// doesn't match `/` while `**` does. All entries within the directory are
+/// considered, recursively, without following symbolic links.
+///
+/// There are three modes:
+///
+/// - [`exactly`](Self::exactly): every pattern matches at least one entry, and
+///   every entry matches a pattern. Directories containing a matching entry
+///   don't need to be listed.
+/// - [`at_least`](Self::at_least): every pattern matches at least one entry.
+/// - [`none_of`](Self::none_of): no entry matches any of the patterns.
+///
+/// On failure, patterns that didn't match any entry are listed as `missing`,
+/// and entries that weren't expected are listed as `unexpected`.
+///
+/// # Examples
+///
+/// ```rust
+/// use camino_tempfile_ext::{assert::DirContentsPredicate, prelude::*};
+///
+/// let temp = Utf8TempDir::new().unwrap();
+/// temp.child("a.txt").touch().unwrap();
+/// temp.child("b/c.json").touch().unwrap();
+///
+/// temp.assert(DirContentsPredicate::exactly(["a.txt", "b/*.json"]));
+/// temp.assert(DirContentsPredicate::at_least(["b/c.json"]));
+/// temp.assert(DirContentsPredicate::none_of(["**/*.tmp"]));
+///
+/// temp.close().unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct DirContentsPredicate {
+    mode: DirContentsMode,
+    patterns: Vec<(String, globset::GlobMatcher)>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum DirContentsMode {
+    Exactly,
+    AtLeast,
+    NoneOf,
+}
+
+impl DirContentsPredicate {
+    /// Create a predicate that the directory contains exactly the paths
+    /// matching `patterns`, and nothing else.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a pattern isn't a valid glob.
+    #[track_caller]
+    pub fn exactly<I, S>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self::new(DirContentsMode::Exactly, patterns)
+    }
+
+    /// Create a predicate that the directory contains paths matching each of
+    /// `patterns`, and possibly other paths.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a pattern isn't a valid glob.
+    #[track_caller]
+    pub fn at_least<I, S>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self::new(DirContentsMode::AtLeast, patterns)
+    }
+
+    /// Create a predicate that the directory contains no paths matching any of
+    /// `patterns`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a pattern isn't a valid glob.
+    #[track_caller]
+    pub fn none_of<I, S>(patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Self::new(DirContentsMode::NoneOf, patterns)
+    }
+
+    #[track_caller]
+    fn new<I, S>(mode: DirContentsMode, patterns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let patterns = patterns
+            .into_iter()
+            .map(|pattern| {
+                let pattern = pattern.as_ref();
+                let matcher = globset::GlobBuilder::new(pattern)
+                    .literal_separator(true)
+                    .build()
+                    .unwrap_or_else(|error| panic!("invalid glob pattern `{pattern}`: {error}"))
+                    .compile_matcher();
+                (pattern.to_owned(), matcher)
+            })
+            .collect();
+        Self { mode, patterns }
+    }
+
+    /// Returns the patterns that didn't match any entry, and the entries that
+    /// weren't expected.
+    fn check(&self, dir: &Utf8Path) -> io::Result<(Vec<&str>, Vec<String>)> {
+        let mut entries = Vec::new();
+        list_entries(dir.as_std_path(), "", &mut entries)?;
+        entries.sort();
+
+        let matches = |path: &str| {
+            self.patterns
+                .iter()
+                .any(|(_, matcher)| matcher.is_match(path))
+        };
+        let missing = match self.mode {
+            DirContentsMode::Exactly | DirContentsMode::AtLeast => self
+                .patterns
+                .iter()
+                .filter(|(_, matcher)| !entries.iter().any(|(path, _)| matcher.is_match(path)))
+                .map(|(pattern, _)| pattern.as_str())
+                .collect(),
+            DirContentsMode::NoneOf => Vec::new(),
+        };
+        let unexpected = match self.mode {
+            DirContentsMode::Exactly => {
+                let matched: Vec<&str> = entries
+                    .iter()
+                    .map(|(path, _)| path.as_str())
+                    .filter(|path| matches(path))
+                    .collect();
+                entries
+                    .iter()
+                    .filter(|(path, is_dir)| {
+                        // Directories are implied by the entries within them.
+                        let implied = *is_dir
+                            && matched.iter().any(|m| {
+                                m.strip_prefix(path.as_str())
+                                    .is_some_and(|rest| rest.starts_with('/'))
+                            });
+                        !matches(path) && !implied
+                    })
+                    .map(|(path, _)| path.clone())
+                    .collect()
+            }
+            DirContentsMode::AtLeast => Vec::new(),
+            DirContentsMode::NoneOf => entries
+                .into_iter()
+                .filter(|(path, _)| matches(path))
+                .map(|(path, _)| path)
+                .collect(),
+        };
+        Ok((missing, unexpected))
+    }
+}
+
+/// Appends the paths of all entries within `dir`, relative to the directory
+/// originally listed and prefixed with `prefix`, along with whether each entry
+/// is a directory.
+fn list_entries(dir: &Path, prefix: &str, entries: &mut Vec<(String, bool)>) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = format!("{prefix}{}", entry.file_name().to_string_lossy());
+        // `file_type` doesn't follow symbolic links.
+        let is_dir = entry.file_type()?.is_dir();
+        if is_dir {
+            list_entries(&entry.path(), &format!("{path}/"), entries)?;
+        }
+        entries.push((path, is_dir));
+    }
+    Ok(())
+}
+
+impl PredicateReflection for DirContentsPredicate {}
+
+impl Predicate<Utf8Path> for DirContentsPredicate {
+    fn eval(&self, item: &Utf8Path) -> bool {
+        self.check(item)
+            .is_ok_and(|(missing, unexpected)| missing.is_empty() && unexpected.is_empty())
+    }
+
+    fn find_case<'a>(&'a self, expected: bool, variable: &Utf8Path) -> Option<Case<'a>> {
+        let check = self.check(variable);
+        let result = check
+            .as_ref()
+            .is_ok_and(|(missing, unexpected)| missing.is_empty() && unexpected.is_empty());
+        if result != expected {
+            return None;
+        }
+
+        let mut case = Case::new(Some(self), result);
+        match check {
+            Ok((missing, unexpected)) => {
+                for pattern in missing {
+                    case = case.add_product(Product::new("missing", pattern.to_owned()));
+                }
+                for path in unexpected {
+                    case = case.add_product(Product::new("unexpected", path));
+                }
+            }
+            Err(error) => {
+                case = case.add_product(Product::new("error", error.to_string()));
+            }
+        }
+        Some(case)
+    }
+}
+
+impl fmt::Display for DirContentsPredicate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mode = match self.mode {
+            DirContentsMode::Exactly => "exactly",
+            DirContentsMode::AtLeast => "at least",
+            DirContentsMode::NoneOf => "none of",
+        };
+        write!(f, "dir contains {mode} [")?;
+        for (i, (pattern, _)) in self.patterns.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            f.write_str(pattern)?;
+        }
+        f.write_str("]")
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
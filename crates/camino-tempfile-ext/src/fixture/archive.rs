@@ -0,0 +1,693 @@
+// Copyright (c) The camino-tempfile Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use super::{ChildPath, FixtureError, FixtureKind, ResultChainExt};
+use camino::{Utf8Path, Utf8PathBuf};
+use camino_tempfile::Utf8TempDir;
+use std::{
+    cell::Cell,
+    env,
+    fs::{self, File},
+    io::{self, Read, Seek},
+    path::{Component, Path, PathBuf},
+    thread,
+};
+
+/// The environment variable naming the directory that [`ExportOnPanic`] writes archives to.
+pub const ARTIFACTS_ENV: &str = "CAMINO_TEMPFILE_ARTIFACTS";
+
+/// Populate a [`ChildPath`] or [`Utf8TempDir`] from a tar, tar.gz or zip archive.
+///
+/// The format is detected from the contents of the archive rather than from its extension.
+///
+/// Unix permissions and symbolic links are preserved. Entries with absolute paths or `..`
+/// components, and entries that would be written through a symbolic link, are rejected. On
+/// failure, the returned [`FixtureError`] has kind [`FixtureKind::Extract`], and
+/// [`FixtureError::path`] is the path of the failing entry within the archive.
+///
+/// This trait is only available with the `archive` feature enabled.
+pub trait PathExtract {
+    /// Extract the archive at `archive` into this directory, creating it if necessary.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use camino_tempfile_ext::prelude::*;
+    ///
+    /// let temp = Utf8TempDir::new().unwrap();
+    /// temp.extract("tests/fixtures/project.tar.gz").unwrap();
+    /// temp.child("data").extract("tests/fixtures/data.zip").unwrap();
+    /// temp.close().unwrap();
+    /// ```
+    fn extract<P: AsRef<Path>>(&self, archive: P) -> Result<(), FixtureError>;
+}
+
+impl PathExtract for Utf8TempDir {
+    fn extract<P: AsRef<Path>>(&self, archive: P) -> Result<(), FixtureError> {
+        extract(self.path(), archive.as_ref())
+    }
+}
+
+impl PathExtract for ChildPath {
+    fn extract<P: AsRef<Path>>(&self, archive: P) -> Result<(), FixtureError> {
+        extract(self.as_path(), archive.as_ref())
+    }
+}
+
+/// Pack a [`ChildPath`] or [`Utf8TempDir`] into a tar.gz archive, for example to keep the contents
+/// of a temporary directory around as a CI artifact.
+///
+/// Archives can be extracted with `tar -xzf`, or with [`PathExtract::extract`]. Symbolic links are
+/// stored as links rather than followed.
+///
+/// This trait is only available with the `archive` feature enabled.
+pub trait PathExport {
+    /// Pack this directory into a tar.gz archive at `dest`, creating parent directories as
+    /// needed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use camino_tempfile_ext::prelude::*;
+    ///
+    /// let temp = Utf8TempDir::new().unwrap();
+    /// temp.child("data/foo.txt").write_str("Hello, world!").unwrap();
+    ///
+    /// let artifacts = Utf8TempDir::new().unwrap();
+    /// temp.child("data")
+    ///     .export_tar_gz(artifacts.path().join("data.tar.gz"))
+    ///     .unwrap();
+    ///
+    /// temp.close().unwrap();
+    /// artifacts.close().unwrap();
+    /// ```
+    fn export_tar_gz<P: AsRef<Path>>(&self, dest: P) -> Result<(), FixtureError>;
+
+    /// Return a guard that exports this directory if the current thread panics while the guard
+    /// is alive.
+    ///
+    /// See [`ExportOnPanic`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use camino_tempfile_ext::prelude::*;
+    ///
+    /// let temp = Utf8TempDir::new().unwrap();
+    /// // Declare the guard after the directory, so that it's dropped first.
+    /// let _guard = temp.export_on_panic();
+    ///
+    /// temp.child("foo.txt").write_str("Hello, world!").unwrap();
+    /// // ... assertions that may panic ...
+    /// ```
+    fn export_on_panic(&self) -> ExportOnPanic<'_>;
+}
+
+impl PathExport for Utf8TempDir {
+    fn export_tar_gz<P: AsRef<Path>>(&self, dest: P) -> Result<(), FixtureError> {
+        export_tar_gz(self.path(), dest.as_ref())
+    }
+
+    fn export_on_panic(&self) -> ExportOnPanic<'_> {
+        ExportOnPanic::new(self.path())
+    }
+}
+
+impl PathExport for ChildPath {
+    fn export_tar_gz<P: AsRef<Path>>(&self, dest: P) -> Result<(), FixtureError> {
+        export_tar_gz(self.as_path(), dest.as_ref())
+    }
+
+    fn export_on_panic(&self) -> ExportOnPanic<'_> {
+        ExportOnPanic::new(self.as_path())
+    }
+}
+
+/// A guard that packs a directory into a tar.gz archive if the current thread panics, so that
+/// the contents of a temporary directory survive a failing test.
+///
+/// Archives are written to the directory named by the [`ARTIFACTS_ENV`] environment variable
+/// (`CAMINO_TEMPFILE_ARTIFACTS`), read when the guard is created. If the variable isn't set,
+/// nothing is exported. Each archive is named after the current thread, which the Rust test
+/// harness names after the running test, followed by the name of the exported directory and a
+/// number counting the guards created on the thread, so that guards for directories with the same
+/// name don't overwrite each other's archives.
+///
+/// The guard must be dropped before the directory is removed: declare it after the
+/// [`Utf8TempDir`], since local variables are dropped in reverse order. Errors while exporting
+/// are printed to standard error rather than causing a second panic.
+///
+/// Create a guard with [`PathExport::export_on_panic`] or [`ExportOnPanic::new`].
+///
+/// This type is only available with the `archive` feature enabled.
+#[derive(Debug)]
+#[must_use = "the directory is only exported while the guard is alive"]
+pub struct ExportOnPanic<'a> {
+    path: &'a Utf8Path,
+    artifacts_dir: Option<Utf8PathBuf>,
+    name: String,
+}
+
+impl<'a> ExportOnPanic<'a> {
+    /// Create a guard that exports the directory at `path` on panic.
+    pub fn new(path: &'a Utf8Path) -> Self {
+        thread_local! {
+            static GUARD_COUNT: Cell<usize> = const { Cell::new(0) };
+        }
+
+        let count = GUARD_COUNT.with(|count| {
+            count.set(count.get() + 1);
+            count.get()
+        });
+        let thread = thread::current();
+        let name = format!(
+            "{}-{}-{count}",
+            thread.name().unwrap_or("unnamed"),
+            path.file_name().unwrap_or("root")
+        );
+        Self {
+            path,
+            artifacts_dir: env::var_os(ARTIFACTS_ENV)
+                .filter(|dir| !dir.is_empty())
+                .map(|dir| Utf8PathBuf::from(dir.to_string_lossy().into_owned())),
+            name,
+        }
+    }
+
+    /// Write the archive to `dir` instead of the directory named by [`ARTIFACTS_ENV`].
+    pub fn artifacts_dir(mut self, dir: impl Into<Utf8PathBuf>) -> Self {
+        self.artifacts_dir = Some(dir.into());
+        self
+    }
+
+    /// Name the archive `name`, followed by `.tar.gz`, instead of deriving the name from the
+    /// current thread and directory.
+    ///
+    /// Guards with the same name write to the same archive.
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.name = name.into();
+        self
+    }
+
+    /// Return the path the archive is written to on panic, if an artifacts directory is set.
+    pub fn archive_path(&self) -> Option<Utf8PathBuf> {
+        // Test names contain `::`, and may contain other characters that
+        // aren't allowed in file names on some platforms.
+        let name: String = self
+            .name
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        let dir = self.artifacts_dir.as_ref()?;
+        Some(dir.join(format!("{name}.tar.gz")))
+    }
+}
+
+impl Drop for ExportOnPanic<'_> {
+    fn drop(&mut self) {
+        if !thread::panicking() {
+            return;
+        }
+        let Some(archive) = self.archive_path() else {
+            return;
+        };
+        match export_tar_gz(self.path, archive.as_std_path()) {
+            Ok(()) => eprintln!("exported `{}` to `{archive}`", self.path),
+            Err(error) => {
+                eprint!("failed to export `{}` to `{archive}`: {error}", self.path);
+                if let Some(source) = std::error::Error::source(&error) {
+                    eprint!(": {source}");
+                }
+                eprintln!();
+            }
+        }
+    }
+}
+
+fn extract(target: &Utf8Path, archive: &Path) -> Result<(), FixtureError> {
+    fs::create_dir_all(target).chain(FixtureError::new(FixtureKind::CreateDir))?;
+
+    let mut file = File::open(archive).chain(FixtureError::new(FixtureKind::Extract))?;
+    let mut magic = Vec::with_capacity(4);
+    file.by_ref()
+        .take(4)
+        .read_to_end(&mut magic)
+        .chain(FixtureError::new(FixtureKind::Extract))?;
+    file.rewind()
+        .chain(FixtureError::new(FixtureKind::Extract))?;
+
+    let target = target.as_std_path();
+    // A tar archive starts with the name of its first entry, which may itself
+    // start with `PK`, so match the full zip signatures: a local file header,
+    // an empty archive's end of central directory record, or a spanned
+    // archive's marker.
+    if matches!(
+        magic.as_slice(),
+        b"PK\x03\x04" | b"PK\x05\x06" | b"PK\x07\x08"
+    ) {
+        extract_zip(target, file)
+    } else if magic.starts_with(&[0x1f, 0x8b]) {
+        extract_tar(target, flate2::read::MultiGzDecoder::new(file))
+    } else {
+        extract_tar(target, file)
+    }
+}
+
+fn extract_tar<R: Read>(target: &Path, reader: R) -> Result<(), FixtureError> {
+    let mut archive = tar::Archive::new(reader);
+    archive.set_preserve_permissions(true);
+
+    // Directory permissions are set last, so that read-only directories can
+    // still be populated.
+    let mut dirs = Vec::new();
+    for entry in archive
+        .entries()
+        .chain(FixtureError::new(FixtureKind::Extract))?
+    {
+        let mut entry = entry.chain(FixtureError::new(FixtureKind::Extract))?;
+        let path = entry
+            .path()
+            .chain(FixtureError::new(FixtureKind::Extract))?
+            .into_owned();
+        let entry_error = || {
+            FixtureError::new(FixtureKind::Extract)
+                .with_path(Utf8PathBuf::from(path.to_string_lossy().into_owned()))
+        };
+
+        let rel = check_entry_path(&path).chain(entry_error())?;
+        if entry.header().entry_type().is_dir() {
+            let dest = prepare_entry(target, &rel, true).chain(entry_error())?;
+            let mode = entry.header().mode().chain(entry_error())?;
+            dirs.push((dest, Some(mode), path));
+        } else {
+            // `unpack_in` also checks that the entry isn't written through a
+            // symbolic link.
+            match entry.unpack_in(target) {
+                Ok(true) => {}
+                Ok(false) => return Err(entry_error().with_source(escapes_target())),
+                Err(error) => return Err(entry_error().with_source(error)),
+            }
+        }
+    }
+
+    set_dir_modes(dirs)
+}
+
+fn extract_zip(target: &Path, file: File) -> Result<(), FixtureError> {
+    let mut archive = zip::ZipArchive::new(file).chain(FixtureError::new(FixtureKind::Extract))?;
+
+    // Directory permissions are set last, so that read-only directories can
+    // still be populated.
+    let mut dirs = Vec::new();
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .chain(FixtureError::new(FixtureKind::Extract))?;
+        let path = PathBuf::from(entry.name());
+        let entry_error = || {
+            FixtureError::new(FixtureKind::Extract)
+                .with_path(Utf8PathBuf::from(path.to_string_lossy().into_owned()))
+        };
+
+        let rel = check_entry_path(&path).chain(entry_error())?;
+        let mode = entry.unix_mode();
+        if entry.is_dir() {
+            let dest = prepare_entry(target, &rel, true).chain(entry_error())?;
+            dirs.push((dest, mode, path));
+        } else if entry.is_symlink() {
+            let mut link_target = String::new();
+            entry
+                .read_to_string(&mut link_target)
+                .chain(entry_error())?;
+            let dest = prepare_entry(target, &rel, false).chain(entry_error())?;
+            symlink(Path::new(&link_target), &dest).chain(entry_error())?;
+        } else {
+            let dest = prepare_entry(target, &rel, false).chain(entry_error())?;
+            let mut out = File::create(&dest).chain(entry_error())?;
+            io::copy(&mut entry, &mut out).chain(entry_error())?;
+            if let Some(mode) = mode {
+                set_mode(&dest, mode).chain(entry_error())?;
+            }
+        }
+    }
+
+    set_dir_modes(dirs)
+}
+
+/// Returns the path of an entry relative to the target directory, rejecting
+/// absolute paths and `..` components.
+fn check_entry_path(path: &Path) -> io::Result<PathBuf> {
+    let mut rel = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(component) => rel.push(component),
+            Component::CurDir => {}
+            Component::ParentDir | Component::RootDir | Component::Prefix(_) => {
+                return Err(escapes_target());
+            }
+        }
+    }
+    Ok(rel)
+}
+
+fn escapes_target() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "entry path is absolute or contains `..`",
+    )
+}
+
+/// Prepares to extract the entry at `rel` within `target`, returning its
+/// destination path.
+///
+/// Directory entries are created. For other entries, parent directories are
+/// created, and an existing non-directory at the destination is removed so that
+/// it is replaced rather than written through.
+fn prepare_entry(target: &Path, rel: &Path, is_dir: bool) -> io::Result<PathBuf> {
+    // Check that no existing path that the entry is extracted under is a
+    // symbolic link, which could point outside of `target`.
+    let mut dest = target.to_path_buf();
+    let mut components = rel.components().peekable();
+    while let Some(component) = components.next() {
+        dest.push(component);
+        if components.peek().is_none() && !is_dir {
+            break;
+        }
+        match fs::symlink_metadata(&dest) {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "entry path is inside a symbolic link",
+                ));
+            }
+            Ok(_) => {}
+            Err(error) if error.kind() == io::ErrorKind::NotFound => break,
+            Err(error) => return Err(error),
+        }
+    }
+
+    let dest = target.join(rel);
+    if is_dir {
+        fs::create_dir_all(&dest)?;
+    } else {
+        if let Some(parent) = dest.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        match fs::symlink_metadata(&dest) {
+            Ok(metadata) if !metadata.is_dir() => fs::remove_file(&dest)?,
+            _ => {}
+        }
+    }
+    Ok(dest)
+}
+
+fn set_dir_modes(dirs: Vec<(PathBuf, Option<u32>, PathBuf)>) -> Result<(), FixtureError> {
+    // Set permissions on children before their parents.
+    for (dest, mode, path) in dirs.into_iter().rev() {
+        if let Some(mode) = mode {
+            set_mode(&dest, mode).chain(
+                FixtureError::new(FixtureKind::Extract)
+                    .with_path(Utf8PathBuf::from(path.to_string_lossy().into_owned())),
+            )?;
+        }
+    }
+    Ok(())
+}
+
+fn export_tar_gz(source: &Utf8Path, dest: &Path) -> Result<(), FixtureError> {
+    use std::io::Write;
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).chain(FixtureError::new(FixtureKind::CreateDir))?;
+    }
+    let file = File::create(dest).chain(FixtureError::new(FixtureKind::Export))?;
+    let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+    let mut builder = tar::Builder::new(encoder);
+    builder.follow_symlinks(false);
+    builder
+        .append_dir_all(".", source)
+        .chain(FixtureError::new(FixtureKind::Export).with_path(source))?;
+    builder
+        .into_inner()
+        .and_then(|encoder| encoder.finish())
+        .and_then(|mut file| file.flush())
+        .chain(FixtureError::new(FixtureKind::Export))?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(path, fs::Permissions::from_mode(mode & 0o7777))
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _mode: u32) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(windows)]
+fn symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::windows::fs::symlink_file(target, link)
+}
+
+#[cfg(not(windows))]
+fn symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::{FileWriteBin, PathChild};
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    fn tar_gz(build: impl FnOnce(&mut tar::Builder<Vec<u8>>)) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        build(&mut builder);
+        let tar = builder.into_inner().unwrap();
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(&tar).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn header(entry_type: tar::EntryType, mode: u32, size: u64) -> tar::Header {
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(entry_type);
+        header.set_mode(mode);
+        header.set_size(size);
+        header
+    }
+
+    fn zip(build: impl FnOnce(&mut zip::ZipWriter<io::Cursor<Vec<u8>>>)) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(io::Cursor::new(Vec::new()));
+        build(&mut writer);
+        writer.finish().unwrap().into_inner()
+    }
+
+    #[cfg(unix)]
+    fn mode(path: &Utf8Path) -> u32 {
+        use std::os::unix::fs::PermissionsExt;
+
+        path.symlink_metadata().unwrap().permissions().mode() & 0o7777
+    }
+
+    #[test]
+    fn test_extract_tar_gz() {
+        let temp_dir = Utf8TempDir::new().unwrap();
+        let archive = temp_dir.child("archive.tar.gz");
+        archive
+            .write_binary(&tar_gz(|builder| {
+                let mut dir = header(tar::EntryType::Directory, 0o755, 0);
+                builder.append_data(&mut dir, "dir", io::empty()).unwrap();
+                let mut script = header(tar::EntryType::Regular, 0o750, 5);
+                builder
+                    .append_data(&mut script, "dir/script.sh", &b"hello"[..])
+                    .unwrap();
+                let mut link = header(tar::EntryType::Symlink, 0o777, 0);
+                builder
+                    .append_link(&mut link, "link", "dir/script.sh")
+                    .unwrap();
+            }))
+            .unwrap();
+
+        let out = temp_dir.child("out");
+        out.extract(&archive).unwrap();
+        assert_eq!(
+            fs::read_to_string(out.join("dir/script.sh")).unwrap(),
+            "hello"
+        );
+        assert_eq!(out.join("link").read_link_utf8().unwrap(), "dir/script.sh");
+        #[cfg(unix)]
+        assert_eq!(mode(&out.join("dir/script.sh")), 0o750);
+    }
+
+    #[test]
+    fn test_extract_tar_starting_with_pk() {
+        let temp_dir = Utf8TempDir::new().unwrap();
+        let archive = temp_dir.child("archive.tar");
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut pkgbuild = header(tar::EntryType::Regular, 0o644, 5);
+        builder
+            .append_data(&mut pkgbuild, "PKGBUILD", &b"hello"[..])
+            .unwrap();
+        archive
+            .write_binary(&builder.into_inner().unwrap())
+            .unwrap();
+
+        let out = temp_dir.child("out");
+        out.extract(&archive).unwrap();
+        assert_eq!(fs::read_to_string(out.join("PKGBUILD")).unwrap(), "hello");
+    }
+
+    #[test]
+    fn test_extract_zip() {
+        let temp_dir = Utf8TempDir::new().unwrap();
+        let archive = temp_dir.child("archive.zip");
+        archive
+            .write_binary(&zip(|writer| {
+                let options = SimpleFileOptions::default();
+                writer.add_directory("dir/", options).unwrap();
+                writer
+                    .start_file("dir/script.sh", options.unix_permissions(0o750))
+                    .unwrap();
+                writer.write_all(b"hello").unwrap();
+                writer
+                    .add_symlink("link", "dir/script.sh", options)
+                    .unwrap();
+            }))
+            .unwrap();
+
+        temp_dir.extract(&archive).unwrap();
+        let script = temp_dir.child("dir/script.sh");
+        assert_eq!(fs::read_to_string(&script).unwrap(), "hello");
+        assert_eq!(
+            temp_dir.child("link").read_link_utf8().unwrap(),
+            "dir/script.sh"
+        );
+        #[cfg(unix)]
+        assert_eq!(mode(&script), 0o750);
+    }
+
+    #[test]
+    fn test_export_tar_gz() {
+        let temp_dir = Utf8TempDir::new().unwrap();
+        let source = temp_dir.child("source");
+        source.child("dir/file.txt").write_binary(b"hello").unwrap();
+        let archive = temp_dir.child("artifacts/source.tar.gz");
+        source.export_tar_gz(&archive).unwrap();
+
+        let out = temp_dir.child("out");
+        out.extract(&archive).unwrap();
+        assert_eq!(fs::read(out.join("dir/file.txt")).unwrap(), b"hello");
+
+        let error = temp_dir
+            .child("missing")
+            .export_tar_gz(temp_dir.path().join("missing.tar.gz"))
+            .unwrap_err();
+        assert_eq!(error.kind(), FixtureKind::Export);
+    }
+
+    #[test]
+    fn test_export_on_panic() {
+        let artifacts = Utf8TempDir::new().unwrap();
+
+        let artifacts_dir = artifacts.path().to_owned();
+        let result = thread::Builder::new()
+            .name("tests::failing".to_owned())
+            .spawn(move || {
+                let temp_dir = Utf8TempDir::new().unwrap();
+                let _guard = temp_dir.export_on_panic().artifacts_dir(&artifacts_dir);
+                temp_dir.child("file.txt").write_binary(b"hello").unwrap();
+                panic!("test failed");
+            })
+            .unwrap()
+            .join();
+        assert!(result.is_err());
+
+        let archives: Vec<_> = artifacts.path().read_dir_utf8().unwrap().collect();
+        assert_eq!(archives.len(), 1);
+        let archive = archives[0].as_ref().unwrap().path().to_owned();
+        assert!(archive.file_name().unwrap().starts_with("tests__failing-"));
+        let out = artifacts.child("out");
+        out.extract(&archive).unwrap();
+        assert_eq!(fs::read(out.join("file.txt")).unwrap(), b"hello");
+
+        // Nothing is exported without a panic.
+        let temp_dir = Utf8TempDir::new().unwrap();
+        let guard = temp_dir
+            .export_on_panic()
+            .artifacts_dir(artifacts.path())
+            .name("passing");
+        assert_eq!(
+            guard.archive_path(),
+            Some(artifacts.path().join("passing.tar.gz"))
+        );
+        drop(guard);
+        assert!(!artifacts.path().join("passing.tar.gz").exists());
+
+        // Directories with the same name are exported to different archives.
+        let first = temp_dir.child("a/data");
+        let second = temp_dir.child("b/data");
+        let first_guard = first.export_on_panic().artifacts_dir(artifacts.path());
+        let second_guard = second.export_on_panic().artifacts_dir(artifacts.path());
+        assert_ne!(first_guard.archive_path(), second_guard.archive_path());
+    }
+
+    #[test]
+    fn test_extract_rejects_escaping_entries() {
+        let temp_dir = Utf8TempDir::new().unwrap();
+        let out = temp_dir.child("out");
+
+        // `tar::Builder` refuses to write `..` paths, so set the name directly.
+        let archive = temp_dir.child("parent.tar.gz");
+        archive
+            .write_binary(&tar_gz(|builder| {
+                let mut header = header(tar::EntryType::Regular, 0o644, 4);
+                header.as_old_mut().name[..10].copy_from_slice(b"../escaped");
+                header.set_cksum();
+                builder.append(&header, &b"evil"[..]).unwrap();
+            }))
+            .unwrap();
+        let error = out.extract(&archive).unwrap_err();
+        assert_eq!(error.kind(), FixtureKind::Extract);
+        assert_eq!(error.path(), Some(Utf8Path::new("../escaped")));
+        assert!(!temp_dir.child("escaped").exists());
+
+        let archive = temp_dir.child("absolute.zip");
+        archive
+            .write_binary(&zip(|writer| {
+                writer
+                    .start_file("/escaped", SimpleFileOptions::default())
+                    .unwrap();
+            }))
+            .unwrap();
+        let error = out.extract(&archive).unwrap_err();
+        assert_eq!(error.kind(), FixtureKind::Extract);
+        assert_eq!(error.path(), Some(Utf8Path::new("/escaped")));
+
+        // A later entry written through an earlier symlink is rejected too.
+        let archive = temp_dir.child("symlink.zip");
+        archive
+            .write_binary(&zip(|writer| {
+                let options = SimpleFileOptions::default();
+                writer
+                    .add_symlink("link", temp_dir.path().as_str(), options)
+                    .unwrap();
+                writer.start_file("link/escaped", options).unwrap();
+            }))
+            .unwrap();
+        let error = out.extract(&archive).unwrap_err();
+        assert_eq!(error.kind(), FixtureKind::Extract);
+        assert_eq!(error.path(), Some(Utf8Path::new("link/escaped")));
+        assert!(!temp_dir.child("escaped").exists());
+    }
+}
@@ -3,6 +3,7 @@
 //
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
+use camino::{Utf8Path, Utf8PathBuf};
 use std::{error::Error, fmt};
 
 pub(crate) trait ChainError {
@@ -39,12 +40,28 @@ pub enum FixtureKind {
     CopyFile,
     /// Failed when writing to a file.
     WriteFile,
+    /// Failed when reading a file.
+    ReadFile,
     /// Failed when creating a directory.
     CreateDir,
     /// Failed to cleanup a fixture.
     Cleanup,
     /// Failed to create a symlink.
     Symlink,
+    /// Failed to create a hard link.
+    Hardlink,
+    /// Failed to set a file's access or modification time.
+    SetTimes,
+    /// Failed to set a path's permissions.
+    SetPermissions,
+    /// Failed to render a template.
+    Template,
+    /// Failed to serialize a value.
+    Serialize,
+    /// Failed to extract an archive.
+    Extract,
+    /// Failed to export an archive.
+    Export,
 }
 
 impl fmt::Display for FixtureKind {
@@ -53,9 +70,17 @@ impl fmt::Display for FixtureKind {
             FixtureKind::Walk => write!(f, "error walking source tree"),
             FixtureKind::CopyFile => write!(f, "error copying file"),
             FixtureKind::WriteFile => write!(f, "error writing file"),
+            FixtureKind::ReadFile => write!(f, "error reading file"),
             FixtureKind::CreateDir => write!(f, "error creating directory"),
             FixtureKind::Cleanup => write!(f, "error cleaning up fixture"),
             FixtureKind::Symlink => write!(f, "error creating symlink to target"),
+            FixtureKind::Hardlink => write!(f, "error creating hard link to target"),
+            FixtureKind::SetTimes => write!(f, "error setting file times"),
+            FixtureKind::SetPermissions => write!(f, "error setting permissions"),
+            FixtureKind::Template => write!(f, "error rendering template"),
+            FixtureKind::Serialize => write!(f, "error serializing value"),
+            FixtureKind::Extract => write!(f, "error extracting archive"),
+            FixtureKind::Export => write!(f, "error exporting archive"),
         }
     }
 }
@@ -64,13 +89,28 @@ impl fmt::Display for FixtureKind {
 #[derive(Debug)]
 pub struct FixtureError {
     kind: FixtureKind,
+    path: Option<Utf8PathBuf>,
     source: Option<Box<dyn Error + Send + Sync + 'static>>,
 }
 
 impl FixtureError {
     /// Create a `FixtureError`.
     pub fn new(kind: FixtureKind) -> Self {
-        Self { kind, source: None }
+        Self {
+            kind,
+            path: None,
+            source: None,
+        }
+    }
+
+    /// Attach the path that the error relates to.
+    ///
+    /// For errors while extracting an archive, this is the path of the failing
+    /// entry within the archive. For errors while reading a file, this is the
+    /// path of the file.
+    pub fn with_path(mut self, path: impl Into<Utf8PathBuf>) -> Self {
+        self.path = Some(path.into());
+        self
     }
 
     /// Attach a source to the error.
@@ -86,6 +126,11 @@ impl FixtureError {
     pub fn kind(&self) -> FixtureKind {
         self.kind
     }
+
+    /// Return the path that the error relates to, if any.
+    pub fn path(&self) -> Option<&Utf8Path> {
+        self.path.as_deref()
+    }
 }
 
 impl Error for FixtureError {
@@ -100,7 +145,11 @@ impl Error for FixtureError {
 
 impl fmt::Display for FixtureError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "failed to initialize fixture: {}", self.kind)
+        write!(f, "failed to initialize fixture: {}", self.kind)?;
+        if let Some(path) = &self.path {
+            write!(f, " (path `{path}`)")?;
+        }
+        Ok(())
     }
 }
 
@@ -140,5 +189,13 @@ mod tests {
             error.source().unwrap().to_string(),
             "failed to initialize fixture: error copying file"
         );
+
+        // with a path
+        let error = FixtureError::new(FixtureKind::Extract).with_path("foo/bar");
+        assert_eq!(error.path(), Some(Utf8Path::new("foo/bar")));
+        assert_eq!(
+            error.to_string(),
+            "failed to initialize fixture: error extracting archive (path `foo/bar`)"
+        );
     }
 }
@@ -28,10 +28,18 @@
 //!
 //! [`Utf8TempDir`]: camino_tempfile::Utf8TempDir
 
+#[cfg(feature = "archive")]
+mod archive;
 mod child;
 mod errors;
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+mod serialize;
 mod tools;
 
+#[cfg(feature = "archive")]
+pub use archive::*;
 pub use child::*;
 pub use errors::*;
+#[cfg(any(feature = "json", feature = "toml", feature = "yaml"))]
+pub use serialize::*;
 pub use tools::*;
@@ -0,0 +1,242 @@
+// Copyright (c) The camino-tempfile Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use super::{ChildPath, FixtureError, FixtureKind, ResultChainExt, tools::write_str};
+use camino::Utf8Path;
+use camino_tempfile::NamedUtf8TempFile;
+use serde::Serialize;
+
+/// Write a value serialized as JSON at a [`ChildPath`] or [`NamedUtf8TempFile`].
+///
+/// This trait is only available with the `json` feature enabled.
+#[cfg(feature = "json")]
+pub trait FileWriteJson {
+    /// Write `value` as compact JSON at [`ChildPath`] or [`NamedUtf8TempFile`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use camino_tempfile_ext::prelude::*;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let temp = Utf8TempDir::new().unwrap();
+    /// let config = temp.child("config.json");
+    /// config.write_json(&BTreeMap::from([("port", 8080)])).unwrap();
+    /// assert_eq!(std::fs::read_to_string(&config).unwrap(), r#"{"port":8080}"#);
+    /// temp.close().unwrap();
+    /// ```
+    fn write_json<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), FixtureError>;
+
+    /// Write `value` as pretty-printed JSON at [`ChildPath`] or [`NamedUtf8TempFile`].
+    fn write_json_pretty<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), FixtureError>;
+}
+
+#[cfg(feature = "json")]
+impl FileWriteJson for ChildPath {
+    fn write_json<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), FixtureError> {
+        write_json(self.as_path(), value, false)
+    }
+
+    fn write_json_pretty<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), FixtureError> {
+        write_json(self.as_path(), value, true)
+    }
+}
+
+#[cfg(feature = "json")]
+impl FileWriteJson for NamedUtf8TempFile {
+    fn write_json<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), FixtureError> {
+        write_json(self.path(), value, false)
+    }
+
+    fn write_json_pretty<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), FixtureError> {
+        write_json(self.path(), value, true)
+    }
+}
+
+/// Write a value serialized as TOML at a [`ChildPath`] or [`NamedUtf8TempFile`].
+///
+/// This trait is only available with the `toml` feature enabled.
+#[cfg(feature = "toml")]
+pub trait FileWriteToml {
+    /// Write `value` as TOML at [`ChildPath`] or [`NamedUtf8TempFile`].
+    ///
+    /// `value` must serialize to a table, such as a struct or a map.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use camino_tempfile_ext::prelude::*;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let temp = Utf8TempDir::new().unwrap();
+    /// let config = temp.child("config.toml");
+    /// config.write_toml(&BTreeMap::from([("port", 8080)])).unwrap();
+    /// assert_eq!(std::fs::read_to_string(&config).unwrap(), "port = 8080\n");
+    /// temp.close().unwrap();
+    /// ```
+    fn write_toml<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), FixtureError>;
+
+    /// Write `value` as pretty-printed TOML at [`ChildPath`] or [`NamedUtf8TempFile`].
+    ///
+    /// Unlike [`write_toml`](Self::write_toml), arrays are written with one element per line.
+    fn write_toml_pretty<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), FixtureError>;
+}
+
+#[cfg(feature = "toml")]
+impl FileWriteToml for ChildPath {
+    fn write_toml<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), FixtureError> {
+        write_toml(self.as_path(), value, false)
+    }
+
+    fn write_toml_pretty<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), FixtureError> {
+        write_toml(self.as_path(), value, true)
+    }
+}
+
+#[cfg(feature = "toml")]
+impl FileWriteToml for NamedUtf8TempFile {
+    fn write_toml<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), FixtureError> {
+        write_toml(self.path(), value, false)
+    }
+
+    fn write_toml_pretty<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), FixtureError> {
+        write_toml(self.path(), value, true)
+    }
+}
+
+/// Write a value serialized as YAML at a [`ChildPath`] or [`NamedUtf8TempFile`].
+///
+/// YAML is always written in block style, with one entry per line.
+///
+/// This trait is only available with the `yaml` feature enabled.
+#[cfg(feature = "yaml")]
+pub trait FileWriteYaml {
+    /// Write `value` as YAML at [`ChildPath`] or [`NamedUtf8TempFile`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use camino_tempfile_ext::prelude::*;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let temp = Utf8TempDir::new().unwrap();
+    /// let config = temp.child("config.yaml");
+    /// config.write_yaml(&BTreeMap::from([("port", 8080)])).unwrap();
+    /// assert_eq!(std::fs::read_to_string(&config).unwrap(), "port: 8080\n");
+    /// temp.close().unwrap();
+    /// ```
+    fn write_yaml<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), FixtureError>;
+}
+
+#[cfg(feature = "yaml")]
+impl FileWriteYaml for ChildPath {
+    fn write_yaml<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), FixtureError> {
+        write_yaml(self.as_path(), value)
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl FileWriteYaml for NamedUtf8TempFile {
+    fn write_yaml<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), FixtureError> {
+        write_yaml(self.path(), value)
+    }
+}
+
+#[cfg(feature = "json")]
+fn write_json<T: Serialize + ?Sized>(
+    path: &Utf8Path,
+    value: &T,
+    pretty: bool,
+) -> Result<(), FixtureError> {
+    let data = if pretty {
+        serde_json::to_string_pretty(value)
+    } else {
+        serde_json::to_string(value)
+    };
+    write_str(
+        path,
+        &data.chain(FixtureError::new(FixtureKind::Serialize))?,
+    )
+}
+
+#[cfg(feature = "toml")]
+fn write_toml<T: Serialize + ?Sized>(
+    path: &Utf8Path,
+    value: &T,
+    pretty: bool,
+) -> Result<(), FixtureError> {
+    let data = if pretty {
+        toml::to_string_pretty(value)
+    } else {
+        toml::to_string(value)
+    };
+    write_str(
+        path,
+        &data.chain(FixtureError::new(FixtureKind::Serialize))?,
+    )
+}
+
+#[cfg(feature = "yaml")]
+fn write_yaml<T: Serialize + ?Sized>(path: &Utf8Path, value: &T) -> Result<(), FixtureError> {
+    let data = serde_yaml::to_string(value).chain(FixtureError::new(FixtureKind::Serialize))?;
+    write_str(path, &data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixture::PathChild;
+    use camino_tempfile::Utf8TempDir;
+    use std::{collections::BTreeMap, fs};
+
+    fn config() -> BTreeMap<&'static str, Vec<u32>> {
+        BTreeMap::from([("ports", vec![80, 443])])
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn test_write_json() {
+        let temp_dir = Utf8TempDir::new().unwrap();
+        let file = temp_dir.child("dir/config.json");
+        file.write_json(&config()).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), r#"{"ports":[80,443]}"#);
+        file.write_json_pretty(&config()).unwrap();
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "{\n  \"ports\": [\n    80,\n    443\n  ]\n}"
+        );
+
+        // JSON object keys must be strings.
+        let error = file
+            .write_json(&BTreeMap::from([(vec![1], 2)]))
+            .unwrap_err();
+        assert_eq!(error.kind(), FixtureKind::Serialize);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_write_toml() {
+        let temp_dir = Utf8TempDir::new().unwrap();
+        let file = temp_dir.child("dir/config.toml");
+        file.write_toml(&config()).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "ports = [80, 443]\n");
+        file.write_toml_pretty(&config()).unwrap();
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "ports = [\n    80,\n    443,\n]\n"
+        );
+
+        // The top level of a TOML document must be a table.
+        let error = file.write_toml(&42).unwrap_err();
+        assert_eq!(error.kind(), FixtureKind::Serialize);
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_write_yaml() {
+        let temp_dir = Utf8TempDir::new().unwrap();
+        let file = temp_dir.child("dir/config.yaml");
+        file.write_yaml(&config()).unwrap();
+        assert_eq!(fs::read_to_string(&file).unwrap(), "ports:\n- 80\n- 443\n");
+    }
+}
@@ -4,10 +4,17 @@
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
 use super::{ChildPath, FixtureError, FixtureKind, ResultChainExt};
-use camino::Utf8Path;
+use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
 use camino_tempfile::{NamedUtf8TempFile, Utf8TempDir};
 use globwalk::GlobWalkerBuilder;
-use std::{fs, io::Write, path::Path};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fs, io,
+    io::Write,
+    path::Path,
+    time::{Duration, SystemTime},
+};
 
 /// Create empty directories at [`ChildPath`].
 pub trait PathCreateDir {
@@ -119,6 +126,173 @@ impl FileWriteStr for NamedUtf8TempFile {
     }
 }
 
+/// Write a text file at a [`ChildPath`] or [`NamedUtf8TempFile`], substituting placeholders with
+/// paths within a [`Utf8TempDir`] and other values.
+///
+/// See [`TemplateContext`] for the supported placeholders.
+pub trait FileWriteTemplate {
+    /// Render `template` with `context`, and write the result at [`ChildPath`] or
+    /// [`NamedUtf8TempFile`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use camino_tempfile_ext::{fixture::TemplateContext, prelude::*};
+    ///
+    /// let temp = Utf8TempDir::new().unwrap();
+    /// let context = TemplateContext::new(&temp).var("port", "8080");
+    /// let config = temp.child("config.toml");
+    /// config
+    ///     .write_template(
+    ///         "data-dir = \"{{child:data}}\"\nport = {{port}}\n",
+    ///         &context,
+    ///     )
+    ///     .unwrap();
+    ///
+    /// let expected = format!("data-dir = \"{}\"\nport = 8080\n", temp.path().join("data"));
+    /// assert_eq!(std::fs::read_to_string(&config).unwrap(), expected);
+    /// temp.close().unwrap();
+    /// ```
+    fn write_template(
+        &self,
+        template: &str,
+        context: &TemplateContext<'_>,
+    ) -> Result<(), FixtureError>;
+}
+
+impl FileWriteTemplate for ChildPath {
+    fn write_template(
+        &self,
+        template: &str,
+        context: &TemplateContext<'_>,
+    ) -> Result<(), FixtureError> {
+        write_str(self.as_path(), &context.render(template)?)
+    }
+}
+
+impl FileWriteTemplate for NamedUtf8TempFile {
+    fn write_template(
+        &self,
+        template: &str,
+        context: &TemplateContext<'_>,
+    ) -> Result<(), FixtureError> {
+        write_str(self.path(), &context.render(template)?)
+    }
+}
+
+/// Values substituted into templates by [`FileWriteTemplate`].
+///
+/// Placeholders are written as `{{name}}`, with optional whitespace inside the braces. The
+/// following placeholders are supported:
+///
+/// * `{{tempdir}}`: the path to the [`Utf8TempDir`].
+/// * `{{child:rel/path}}`: the path to `rel/path` within the [`Utf8TempDir`].
+/// * `{{name}}`: a variable added with [`TemplateContext::var`]. The built-in placeholders above
+///   take precedence over variables with the same name.
+///
+/// To write a literal `{{`, escape it as `{{{{`. A `}}` outside a placeholder is written as is.
+///
+/// Rendering fails with [`FixtureKind::Template`] if a placeholder is unknown or unclosed, or if a
+/// `child:` path is absolute or contains `..`.
+///
+/// # Examples
+///
+/// ```rust
+/// use camino_tempfile_ext::{fixture::TemplateContext, prelude::*};
+///
+/// let temp = Utf8TempDir::new().unwrap();
+/// let context = TemplateContext::new(&temp).var("name", "world");
+/// assert_eq!(
+///     context.render("{{ tempdir }}: hello, {{name}}!").unwrap(),
+///     format!("{}: hello, world!", temp.path()),
+/// );
+/// assert_eq!(context.render("{{{{ raw }}").unwrap(), "{{ raw }}");
+/// assert!(context.render("{{unknown}}").is_err());
+/// temp.close().unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct TemplateContext<'a> {
+    tempdir: &'a Utf8Path,
+    vars: BTreeMap<String, String>,
+}
+
+impl<'a> TemplateContext<'a> {
+    /// Create a context for paths within `tempdir`, with no variables.
+    pub fn new(tempdir: &'a Utf8TempDir) -> Self {
+        Self {
+            tempdir: tempdir.path(),
+            vars: BTreeMap::new(),
+        }
+    }
+
+    /// Add a variable, substituted for `{{name}}`.
+    ///
+    /// If a variable with the same name was already added, its value is replaced.
+    pub fn var(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.vars.insert(name.into(), value.into());
+        self
+    }
+
+    /// Render `template`, substituting all placeholders.
+    pub fn render(&self, template: &str) -> Result<String, FixtureError> {
+        let mut rendered = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find("{{") {
+            rendered.push_str(&rest[..start]);
+            let after = &rest[start + 2..];
+            if let Some(escaped) = after.strip_prefix("{{") {
+                rendered.push_str("{{");
+                rest = escaped;
+                continue;
+            }
+            let end = after.find("}}").ok_or_else(|| {
+                template_error(format!(
+                    "unclosed placeholder at byte {}",
+                    template.len() - rest.len() + start
+                ))
+            })?;
+            rendered.push_str(&self.resolve(after[..end].trim())?);
+            rest = &after[end + 2..];
+        }
+        rendered.push_str(rest);
+        Ok(rendered)
+    }
+
+    fn resolve(&self, placeholder: &str) -> Result<Cow<'_, str>, FixtureError> {
+        if placeholder == "tempdir" {
+            return Ok(Cow::Borrowed(self.tempdir.as_str()));
+        }
+        if let Some(rel) = placeholder.strip_prefix("child:") {
+            let rel = Utf8Path::new(rel.trim());
+            // Only allow paths within the temporary directory.
+            for component in rel.components() {
+                match component {
+                    Utf8Component::Normal(_) | Utf8Component::CurDir => {}
+                    Utf8Component::ParentDir => {
+                        return Err(template_error(format!(
+                            "path in placeholder `{{{{{placeholder}}}}}` contains `..`"
+                        )));
+                    }
+                    Utf8Component::RootDir | Utf8Component::Prefix(_) => {
+                        return Err(template_error(format!(
+                            "path in placeholder `{{{{{placeholder}}}}}` is absolute"
+                        )));
+                    }
+                }
+            }
+            return Ok(Cow::Owned(self.tempdir.join(rel).into_string()));
+        }
+        self.vars
+            .get(placeholder)
+            .map(|value| Cow::Borrowed(value.as_str()))
+            .ok_or_else(|| template_error(format!("unknown placeholder `{{{{{placeholder}}}}}`")))
+    }
+}
+
+fn template_error(message: String) -> FixtureError {
+    FixtureError::new(FixtureKind::Template).with_source(message)
+}
+
 /// Write (copy) a file to [`ChildPath`].
 pub trait FileWriteFile {
     /// Write (copy) a file to [`ChildPath`].
@@ -150,6 +324,106 @@ impl FileWriteFile for NamedUtf8TempFile {
     }
 }
 
+/// Read back a file at a [`ChildPath`] or [`NamedUtf8TempFile`].
+///
+/// Unlike [`std::fs`] functions, errors have kind [`FixtureKind::ReadFile`] and carry the path
+/// that was read, available through [`FixtureError::path`].
+pub trait FileRead {
+    /// Read the file as a UTF-8 string.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use camino_tempfile_ext::prelude::*;
+    ///
+    /// let temp = Utf8TempDir::new().unwrap();
+    /// let file = temp.child("foo.txt");
+    /// file.write_str("To be or not to be...").unwrap();
+    /// assert_eq!(file.read_str().unwrap(), "To be or not to be...");
+    /// temp.close().unwrap();
+    /// ```
+    fn read_str(&self) -> Result<String, FixtureError>;
+
+    /// Read the file as bytes.
+    fn read_bytes(&self) -> Result<Vec<u8>, FixtureError>;
+
+    /// Read the file as a UTF-8 string, split into lines.
+    ///
+    /// Lines are split as with [`str::lines`], so line endings (`\n` or `\r\n`) are not
+    /// included, and a trailing line ending doesn't produce an empty last line.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use camino_tempfile_ext::prelude::*;
+    ///
+    /// let temp = Utf8TempDir::new().unwrap();
+    /// let file = temp.child("foo.txt");
+    /// file.write_str("one\ntwo\n").unwrap();
+    /// assert_eq!(file.read_lines().unwrap(), ["one", "two"]);
+    /// temp.close().unwrap();
+    /// ```
+    fn read_lines(&self) -> Result<Vec<String>, FixtureError>;
+
+    /// Read the file and deserialize it from JSON.
+    ///
+    /// This method is only available with the `json` feature enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use camino_tempfile_ext::prelude::*;
+    /// use std::collections::BTreeMap;
+    ///
+    /// let temp = Utf8TempDir::new().unwrap();
+    /// let file = temp.child("config.json");
+    /// file.write_str(r#"{"port": 8080}"#).unwrap();
+    /// let config: BTreeMap<String, u16> = file.read_json().unwrap();
+    /// assert_eq!(config["port"], 8080);
+    /// temp.close().unwrap();
+    /// ```
+    #[cfg(feature = "json")]
+    fn read_json<T: serde::de::DeserializeOwned>(&self) -> Result<T, FixtureError>;
+}
+
+impl FileRead for ChildPath {
+    fn read_str(&self) -> Result<String, FixtureError> {
+        read_str(self.as_path())
+    }
+
+    fn read_bytes(&self) -> Result<Vec<u8>, FixtureError> {
+        read_bytes(self.as_path())
+    }
+
+    fn read_lines(&self) -> Result<Vec<String>, FixtureError> {
+        read_lines(self.as_path())
+    }
+
+    #[cfg(feature = "json")]
+    fn read_json<T: serde::de::DeserializeOwned>(&self) -> Result<T, FixtureError> {
+        read_json(self.as_path())
+    }
+}
+
+impl FileRead for NamedUtf8TempFile {
+    fn read_str(&self) -> Result<String, FixtureError> {
+        read_str(self.path())
+    }
+
+    fn read_bytes(&self) -> Result<Vec<u8>, FixtureError> {
+        read_bytes(self.path())
+    }
+
+    fn read_lines(&self) -> Result<Vec<String>, FixtureError> {
+        read_lines(self.path())
+    }
+
+    #[cfg(feature = "json")]
+    fn read_json<T: serde::de::DeserializeOwned>(&self) -> Result<T, FixtureError> {
+        read_json(self.path())
+    }
+}
+
 /// Copy files into [`Utf8TempDir`].
 pub trait PathCopy {
     /// Copy files and directories into the current path from the `source` according to the glob
@@ -191,6 +465,121 @@ impl PathCopy for ChildPath {
     }
 }
 
+/// Find paths within a [`Utf8TempDir`] or [`ChildPath`] that match glob patterns.
+pub trait PathGlob {
+    /// Return the paths within the current path that match the glob `patterns`, sorted.
+    ///
+    /// Patterns use the same gitignore-style syntax as [`PathCopy::copy_from`]. Symbolic links are
+    /// not followed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use camino_tempfile_ext::prelude::*;
+    ///
+    /// let temp = Utf8TempDir::new().unwrap();
+    /// temp.child("a.txt").touch().unwrap();
+    /// temp.child("b/c.txt").touch().unwrap();
+    /// temp.child("b/d.json").touch().unwrap();
+    ///
+    /// let paths = temp.glob(&["*.txt"]).unwrap();
+    /// assert_eq!(paths, [temp.child("a.txt"), temp.child("b/c.txt")]);
+    /// temp.close().unwrap();
+    /// ```
+    fn glob<S: AsRef<str>>(&self, patterns: &[S]) -> Result<Vec<ChildPath>, FixtureError> {
+        self.glob_with(patterns, &GlobOptions::new())
+    }
+
+    /// Return the paths within the current path that match the glob `patterns`, sorted, with
+    /// custom [`GlobOptions`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use camino_tempfile_ext::{fixture::GlobOptions, prelude::*};
+    ///
+    /// let temp = Utf8TempDir::new().unwrap();
+    /// temp.child("a/b.txt").touch().unwrap();
+    ///
+    /// let dirs = temp.glob_with(&["*"], &GlobOptions::new().dirs_only()).unwrap();
+    /// assert_eq!(dirs, [temp.child("a")]);
+    /// temp.close().unwrap();
+    /// ```
+    fn glob_with<S: AsRef<str>>(
+        &self,
+        patterns: &[S],
+        options: &GlobOptions,
+    ) -> Result<Vec<ChildPath>, FixtureError>;
+}
+
+impl PathGlob for Utf8TempDir {
+    fn glob_with<S: AsRef<str>>(
+        &self,
+        patterns: &[S],
+        options: &GlobOptions,
+    ) -> Result<Vec<ChildPath>, FixtureError> {
+        glob(self.path(), patterns, options)
+    }
+}
+
+impl PathGlob for ChildPath {
+    fn glob_with<S: AsRef<str>>(
+        &self,
+        patterns: &[S],
+        options: &GlobOptions,
+    ) -> Result<Vec<ChildPath>, FixtureError> {
+        glob(self.as_path(), patterns, options)
+    }
+}
+
+/// Options for [`PathGlob::glob_with`].
+#[derive(Clone, Debug, Default)]
+pub struct GlobOptions {
+    file_type: GlobFileType,
+    follow_links: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+enum GlobFileType {
+    #[default]
+    Any,
+    Files,
+    Dirs,
+}
+
+impl GlobOptions {
+    /// Create options that match files, directories and symbolic links, without following
+    /// symbolic links.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only match files.
+    ///
+    /// Unless [`follow_links`](Self::follow_links) is enabled, symbolic links to files are not
+    /// matched.
+    pub fn files_only(mut self) -> Self {
+        self.file_type = GlobFileType::Files;
+        self
+    }
+
+    /// Only match directories.
+    ///
+    /// Unless [`follow_links`](Self::follow_links) is enabled, symbolic links to directories are
+    /// not matched.
+    pub fn dirs_only(mut self) -> Self {
+        self.file_type = GlobFileType::Dirs;
+        self
+    }
+
+    /// Follow symbolic links, matching paths within linked directories, and treating links as
+    /// the files or directories they point to.
+    pub fn follow_links(mut self, follow_links: bool) -> Self {
+        self.follow_links = follow_links;
+        self
+    }
+}
+
 /// Create a symlink to a target file.
 pub trait SymlinkToFile {
     /// Create a symlink to the provided target file.
@@ -259,6 +648,405 @@ impl SymlinkToDir for Utf8TempDir {
     }
 }
 
+/// Create a hard link to a target file.
+pub trait HardlinkTo {
+    /// Create a hard link to the provided target file, creating parent directories as needed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use camino_tempfile_ext::prelude::*;
+    ///
+    /// let temp = Utf8TempDir::new().unwrap();
+    /// let real_file = temp.child("real_file");
+    /// real_file.write_str("Hello, world!").unwrap();
+    ///
+    /// let link = temp.child("links/link_file");
+    /// link.hardlink_to(real_file.as_path()).unwrap();
+    /// assert_eq!(std::fs::read_to_string(&link).unwrap(), "Hello, world!");
+    ///
+    /// temp.close().unwrap();
+    /// ```
+    fn hardlink_to<P: AsRef<Path>>(&self, target: P) -> Result<(), FixtureError>;
+}
+
+impl HardlinkTo for ChildPath {
+    fn hardlink_to<P: AsRef<Path>>(&self, target: P) -> Result<(), FixtureError> {
+        hardlink_to(self.as_path(), target.as_ref())
+    }
+}
+
+impl HardlinkTo for NamedUtf8TempFile {
+    fn hardlink_to<P: AsRef<Path>>(&self, target: P) -> Result<(), FixtureError> {
+        hardlink_to(self.path(), target.as_ref())
+    }
+}
+
+/// A point in time to set a file's timestamps to, used by [`FileSetTimes`].
+///
+/// A `FixtureTime` is either an absolute [`SystemTime`], the current time, or
+/// another path's modification time, optionally shifted forwards or backwards
+/// by a [`Duration`]. It is resolved when the timestamps are set.
+///
+/// # Examples
+///
+/// ```rust
+/// use camino_tempfile_ext::fixture::FixtureTime;
+/// use std::time::{Duration, SystemTime};
+///
+/// // An absolute time.
+/// let epoch = FixtureTime::from(SystemTime::UNIX_EPOCH);
+///
+/// // An hour ago.
+/// let stale = FixtureTime::now().minus(Duration::from_secs(3600));
+///
+/// // A second after another file was modified.
+/// let newer = FixtureTime::modified_of("Cargo.toml").plus(Duration::from_secs(1));
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FixtureTime {
+    base: FixtureTimeBase,
+    forwards: Duration,
+    backwards: Duration,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum FixtureTimeBase {
+    At(SystemTime),
+    Now,
+    ModifiedOf(Utf8PathBuf),
+}
+
+impl FixtureTime {
+    /// An absolute point in time.
+    pub fn at(time: SystemTime) -> Self {
+        Self::new(FixtureTimeBase::At(time))
+    }
+
+    /// The current time, as of when the timestamps are set.
+    pub fn now() -> Self {
+        Self::new(FixtureTimeBase::Now)
+    }
+
+    /// The modification time of `path`, as of when the timestamps are set.
+    ///
+    /// Symbolic links are followed.
+    pub fn modified_of<P: Into<Utf8PathBuf>>(path: P) -> Self {
+        Self::new(FixtureTimeBase::ModifiedOf(path.into()))
+    }
+
+    /// Shift this time forwards by `duration`.
+    pub fn plus(mut self, duration: Duration) -> Self {
+        self.forwards += duration;
+        self
+    }
+
+    /// Shift this time backwards by `duration`.
+    pub fn minus(mut self, duration: Duration) -> Self {
+        self.backwards += duration;
+        self
+    }
+
+    fn new(base: FixtureTimeBase) -> Self {
+        Self {
+            base,
+            forwards: Duration::ZERO,
+            backwards: Duration::ZERO,
+        }
+    }
+
+    fn resolve(&self) -> Result<SystemTime, FixtureError> {
+        let base = match &self.base {
+            FixtureTimeBase::At(time) => *time,
+            FixtureTimeBase::Now => SystemTime::now(),
+            FixtureTimeBase::ModifiedOf(path) => fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .chain(FixtureError::new(FixtureKind::SetTimes))?,
+        };
+        base.checked_add(self.forwards)
+            .and_then(|time| time.checked_sub(self.backwards))
+            .ok_or_else(|| {
+                FixtureError::new(FixtureKind::SetTimes).with_source(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "time is out of range",
+                ))
+            })
+    }
+}
+
+impl From<SystemTime> for FixtureTime {
+    fn from(time: SystemTime) -> Self {
+        Self::at(time)
+    }
+}
+
+/// Set the access and modification times of a [`ChildPath`], [`NamedUtf8TempFile`] or
+/// [`Utf8TempDir`].
+///
+/// Times can be absolute [`SystemTime`]s, or relative to the current time or to another path's
+/// modification time; see [`FixtureTime`]. Symbolic links are followed.
+pub trait FileSetTimes {
+    /// Set the modification time, leaving the access time unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use camino_tempfile_ext::{fixture::FixtureTime, prelude::*};
+    /// use std::time::Duration;
+    ///
+    /// let temp = Utf8TempDir::new().unwrap();
+    /// let input = temp.child("input.txt");
+    /// input.touch().unwrap();
+    /// let output = temp.child("output.txt");
+    /// output.touch().unwrap();
+    ///
+    /// // Make the output look out of date.
+    /// let stale = FixtureTime::modified_of(input.as_path()).minus(Duration::from_secs(10));
+    /// output.set_modified(stale).unwrap();
+    ///
+    /// let input_modified = input.metadata().unwrap().modified().unwrap();
+    /// let output_modified = output.metadata().unwrap().modified().unwrap();
+    /// assert!(output_modified < input_modified);
+    /// temp.close().unwrap();
+    /// ```
+    fn set_modified<T: Into<FixtureTime>>(&self, time: T) -> Result<(), FixtureError>;
+
+    /// Set the access time, leaving the modification time unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use camino_tempfile_ext::prelude::*;
+    /// use std::time::SystemTime;
+    ///
+    /// let temp = Utf8TempDir::new().unwrap();
+    /// let file = temp.child("foo.txt");
+    /// file.touch().unwrap();
+    /// file.set_accessed(SystemTime::UNIX_EPOCH).unwrap();
+    /// temp.close().unwrap();
+    /// ```
+    fn set_accessed<T: Into<FixtureTime>>(&self, time: T) -> Result<(), FixtureError>;
+
+    /// Set both the access and modification times.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use camino_tempfile_ext::{fixture::FixtureTime, prelude::*};
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// let temp = Utf8TempDir::new().unwrap();
+    /// let file = temp.child("foo.txt");
+    /// file.touch().unwrap();
+    /// file.set_times(
+    ///     FixtureTime::now(),
+    ///     SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000),
+    /// )
+    /// .unwrap();
+    /// temp.close().unwrap();
+    /// ```
+    fn set_times<A, M>(&self, accessed: A, modified: M) -> Result<(), FixtureError>
+    where
+        A: Into<FixtureTime>,
+        M: Into<FixtureTime>;
+}
+
+impl FileSetTimes for ChildPath {
+    fn set_modified<T: Into<FixtureTime>>(&self, time: T) -> Result<(), FixtureError> {
+        set_times(self.as_path(), None, Some(time.into()))
+    }
+
+    fn set_accessed<T: Into<FixtureTime>>(&self, time: T) -> Result<(), FixtureError> {
+        set_times(self.as_path(), Some(time.into()), None)
+    }
+
+    fn set_times<A, M>(&self, accessed: A, modified: M) -> Result<(), FixtureError>
+    where
+        A: Into<FixtureTime>,
+        M: Into<FixtureTime>,
+    {
+        set_times(self.as_path(), Some(accessed.into()), Some(modified.into()))
+    }
+}
+
+impl FileSetTimes for NamedUtf8TempFile {
+    fn set_modified<T: Into<FixtureTime>>(&self, time: T) -> Result<(), FixtureError> {
+        set_times(self.path(), None, Some(time.into()))
+    }
+
+    fn set_accessed<T: Into<FixtureTime>>(&self, time: T) -> Result<(), FixtureError> {
+        set_times(self.path(), Some(time.into()), None)
+    }
+
+    fn set_times<A, M>(&self, accessed: A, modified: M) -> Result<(), FixtureError>
+    where
+        A: Into<FixtureTime>,
+        M: Into<FixtureTime>,
+    {
+        set_times(self.path(), Some(accessed.into()), Some(modified.into()))
+    }
+}
+
+impl FileSetTimes for Utf8TempDir {
+    fn set_modified<T: Into<FixtureTime>>(&self, time: T) -> Result<(), FixtureError> {
+        set_times(self.path(), None, Some(time.into()))
+    }
+
+    fn set_accessed<T: Into<FixtureTime>>(&self, time: T) -> Result<(), FixtureError> {
+        set_times(self.path(), Some(time.into()), None)
+    }
+
+    fn set_times<A, M>(&self, accessed: A, modified: M) -> Result<(), FixtureError>
+    where
+        A: Into<FixtureTime>,
+        M: Into<FixtureTime>,
+    {
+        set_times(self.path(), Some(accessed.into()), Some(modified.into()))
+    }
+}
+
+/// Change the permissions of a [`ChildPath`], [`NamedUtf8TempFile`] or [`Utf8TempDir`].
+///
+/// This is useful for testing how code handles permission errors, or for running generated
+/// scripts. [`Utf8TempDir`] restores write permissions within the directory as needed when it's
+/// cleaned up, so making parts of it read-only doesn't prevent its removal.
+///
+/// Symbolic links are followed, except by
+/// [`make_read_only_recursive`](Self::make_read_only_recursive).
+pub trait PathSetPermissions {
+    /// Make the path executable by everyone who can read it.
+    ///
+    /// On Windows, whether a file is executable depends on its extension, so this does nothing.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use camino_tempfile_ext::prelude::*;
+    ///
+    /// let temp = Utf8TempDir::new().unwrap();
+    /// let script = temp.child("run.sh");
+    /// script.write_str("#!/bin/sh\necho hello\n").unwrap();
+    /// script.make_executable().unwrap();
+    /// temp.close().unwrap();
+    /// ```
+    fn make_executable(&self) -> Result<(), FixtureError>;
+
+    /// Make the path read-only, removing all write permissions.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use camino_tempfile_ext::prelude::*;
+    ///
+    /// let temp = Utf8TempDir::new().unwrap();
+    /// let file = temp.child("foo.txt");
+    /// file.touch().unwrap();
+    /// file.make_read_only().unwrap();
+    /// assert!(file.metadata().unwrap().permissions().readonly());
+    /// temp.close().unwrap();
+    /// ```
+    fn make_read_only(&self) -> Result<(), FixtureError>;
+
+    /// Make the path writable by its owner, undoing
+    /// [`make_read_only`](Self::make_read_only).
+    fn make_writable(&self) -> Result<(), FixtureError>;
+
+    /// Make the path, and everything under it if it's a directory, read-only.
+    ///
+    /// Symbolic links within the directory are not followed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use camino_tempfile_ext::prelude::*;
+    ///
+    /// let temp = Utf8TempDir::new().unwrap();
+    /// let file = temp.child("foo/bar.txt");
+    /// file.touch().unwrap();
+    /// temp.child("foo").make_read_only_recursive().unwrap();
+    /// assert!(file.metadata().unwrap().permissions().readonly());
+    ///
+    /// // The directory can still be removed.
+    /// temp.close().unwrap();
+    /// ```
+    fn make_read_only_recursive(&self) -> Result<(), FixtureError>;
+
+    /// Set the Unix permission bits of the path to `mode`, for example `0o755`.
+    ///
+    /// This method is only available on Unix.
+    #[cfg(unix)]
+    fn set_mode(&self, mode: u32) -> Result<(), FixtureError>;
+}
+
+impl PathSetPermissions for ChildPath {
+    fn make_executable(&self) -> Result<(), FixtureError> {
+        make_executable(self.as_path())
+    }
+
+    fn make_read_only(&self) -> Result<(), FixtureError> {
+        set_read_only(self.as_path(), true)
+    }
+
+    fn make_writable(&self) -> Result<(), FixtureError> {
+        set_read_only(self.as_path(), false)
+    }
+
+    fn make_read_only_recursive(&self) -> Result<(), FixtureError> {
+        make_read_only_recursive(self.as_std_path())
+    }
+
+    #[cfg(unix)]
+    fn set_mode(&self, mode: u32) -> Result<(), FixtureError> {
+        set_mode(self.as_path(), mode)
+    }
+}
+
+impl PathSetPermissions for NamedUtf8TempFile {
+    fn make_executable(&self) -> Result<(), FixtureError> {
+        make_executable(self.path())
+    }
+
+    fn make_read_only(&self) -> Result<(), FixtureError> {
+        set_read_only(self.path(), true)
+    }
+
+    fn make_writable(&self) -> Result<(), FixtureError> {
+        set_read_only(self.path(), false)
+    }
+
+    fn make_read_only_recursive(&self) -> Result<(), FixtureError> {
+        make_read_only_recursive(self.path().as_std_path())
+    }
+
+    #[cfg(unix)]
+    fn set_mode(&self, mode: u32) -> Result<(), FixtureError> {
+        set_mode(self.path(), mode)
+    }
+}
+
+impl PathSetPermissions for Utf8TempDir {
+    fn make_executable(&self) -> Result<(), FixtureError> {
+        make_executable(self.path())
+    }
+
+    fn make_read_only(&self) -> Result<(), FixtureError> {
+        set_read_only(self.path(), true)
+    }
+
+    fn make_writable(&self) -> Result<(), FixtureError> {
+        set_read_only(self.path(), false)
+    }
+
+    fn make_read_only_recursive(&self) -> Result<(), FixtureError> {
+        make_read_only_recursive(self.path().as_std_path())
+    }
+
+    #[cfg(unix)]
+    fn set_mode(&self, mode: u32) -> Result<(), FixtureError> {
+        set_mode(self.path(), mode)
+    }
+}
+
 fn ensure_parent_dir(path: &Utf8Path) -> Result<(), FixtureError> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent).chain(FixtureError::new(FixtureKind::CreateDir))?;
@@ -285,17 +1073,135 @@ fn write_binary(path: &Utf8Path, data: &[u8]) -> Result<(), FixtureError> {
     Ok(())
 }
 
-fn write_str(path: &Utf8Path, data: &str) -> Result<(), FixtureError> {
+pub(super) fn write_str(path: &Utf8Path, data: &str) -> Result<(), FixtureError> {
     ensure_parent_dir(path)?;
     write_binary(path, data.as_bytes()).chain(FixtureError::new(FixtureKind::WriteFile))
 }
 
+fn read_str(path: &Utf8Path) -> Result<String, FixtureError> {
+    fs::read_to_string(path).chain(FixtureError::new(FixtureKind::ReadFile).with_path(path))
+}
+
+fn read_bytes(path: &Utf8Path) -> Result<Vec<u8>, FixtureError> {
+    fs::read(path).chain(FixtureError::new(FixtureKind::ReadFile).with_path(path))
+}
+
+fn read_lines(path: &Utf8Path) -> Result<Vec<String>, FixtureError> {
+    Ok(read_str(path)?.lines().map(str::to_owned).collect())
+}
+
+#[cfg(feature = "json")]
+fn read_json<T: serde::de::DeserializeOwned>(path: &Utf8Path) -> Result<T, FixtureError> {
+    serde_json::from_str(&read_str(path)?)
+        .chain(FixtureError::new(FixtureKind::ReadFile).with_path(path))
+}
+
 fn write_file(path: &Utf8Path, data: &Utf8Path) -> Result<(), FixtureError> {
     ensure_parent_dir(path)?;
     fs::copy(data, path).chain(FixtureError::new(FixtureKind::CopyFile))?;
     Ok(())
 }
 
+fn set_times(
+    path: &Utf8Path,
+    accessed: Option<FixtureTime>,
+    modified: Option<FixtureTime>,
+) -> Result<(), FixtureError> {
+    // Resolve both times before setting either, so that a time relative to
+    // this path's own modification time uses the original value.
+    let accessed = accessed
+        .map(|time| time.resolve())
+        .transpose()?
+        .map(filetime::FileTime::from_system_time);
+    let modified = modified
+        .map(|time| time.resolve())
+        .transpose()?
+        .map(filetime::FileTime::from_system_time);
+    let result = match (accessed, modified) {
+        (Some(accessed), Some(modified)) => filetime::set_file_times(path, accessed, modified),
+        (Some(accessed), None) => filetime::set_file_atime(path, accessed),
+        (None, Some(modified)) => filetime::set_file_mtime(path, modified),
+        (None, None) => Ok(()),
+    };
+    result.chain(FixtureError::new(FixtureKind::SetTimes))
+}
+
+fn hardlink_to(link: &Utf8Path, target: &Path) -> Result<(), FixtureError> {
+    ensure_parent_dir(link)?;
+    fs::hard_link(target, link).chain(FixtureError::new(FixtureKind::Hardlink))?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Utf8Path) -> Result<(), FixtureError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = fs::metadata(path)
+        .chain(FixtureError::new(FixtureKind::SetPermissions))?
+        .permissions()
+        .mode();
+    // Grant execute permission wherever read permission is granted.
+    set_mode(path, mode | (mode & 0o444) >> 2)
+}
+
+#[cfg(not(unix))]
+fn make_executable(path: &Utf8Path) -> Result<(), FixtureError> {
+    fs::metadata(path).chain(FixtureError::new(FixtureKind::SetPermissions))?;
+    Ok(())
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Utf8Path, mode: u32) -> Result<(), FixtureError> {
+    use std::os::unix::fs::PermissionsExt;
+
+    fs::set_permissions(path, fs::Permissions::from_mode(mode))
+        .chain(FixtureError::new(FixtureKind::SetPermissions))
+}
+
+fn set_read_only(path: &Utf8Path, read_only: bool) -> Result<(), FixtureError> {
+    let mut permissions = fs::metadata(path)
+        .chain(FixtureError::new(FixtureKind::SetPermissions))?
+        .permissions();
+    set_readonly_bits(&mut permissions, read_only);
+    fs::set_permissions(path, permissions).chain(FixtureError::new(FixtureKind::SetPermissions))
+}
+
+fn make_read_only_recursive(path: &Path) -> Result<(), FixtureError> {
+    let metadata =
+        fs::symlink_metadata(path).chain(FixtureError::new(FixtureKind::SetPermissions))?;
+    if metadata.file_type().is_symlink() {
+        return Ok(());
+    }
+    if metadata.is_dir() {
+        // Directories stay readable and searchable, so they can be walked
+        // before being made read-only themselves.
+        for entry in fs::read_dir(path).chain(FixtureError::new(FixtureKind::SetPermissions))? {
+            let entry = entry.chain(FixtureError::new(FixtureKind::SetPermissions))?;
+            make_read_only_recursive(&entry.path())?;
+        }
+    }
+    let mut permissions = metadata.permissions();
+    set_readonly_bits(&mut permissions, true);
+    fs::set_permissions(path, permissions).chain(FixtureError::new(FixtureKind::SetPermissions))
+}
+
+#[cfg(unix)]
+fn set_readonly_bits(permissions: &mut fs::Permissions, read_only: bool) {
+    use std::os::unix::fs::PermissionsExt;
+
+    if read_only {
+        permissions.set_readonly(true);
+    } else {
+        // `set_readonly(false)` would make the path world-writable.
+        permissions.set_mode(permissions.mode() | 0o200);
+    }
+}
+
+#[cfg(not(unix))]
+fn set_readonly_bits(permissions: &mut fs::Permissions, read_only: bool) {
+    permissions.set_readonly(read_only);
+}
+
 fn copy_files<S>(target: &Utf8Path, source: &Utf8Path, patterns: &[S]) -> Result<(), FixtureError>
 where
     S: AsRef<str>,
@@ -331,6 +1237,40 @@ where
     Ok(())
 }
 
+fn glob<S>(
+    root: &Utf8Path,
+    patterns: &[S],
+    options: &GlobOptions,
+) -> Result<Vec<ChildPath>, FixtureError>
+where
+    S: AsRef<str>,
+{
+    let mut builder =
+        GlobWalkerBuilder::from_patterns(root, patterns).follow_links(options.follow_links);
+    match options.file_type {
+        GlobFileType::Any => {}
+        GlobFileType::Files => builder = builder.file_type(globwalk::FileType::FILE),
+        GlobFileType::Dirs => builder = builder.file_type(globwalk::FileType::DIR),
+    }
+
+    let mut paths = Vec::new();
+    for entry in builder
+        .build()
+        .chain(FixtureError::new(FixtureKind::Walk))?
+    {
+        let entry = entry.chain(FixtureError::new(FixtureKind::Walk))?;
+        let path = Utf8PathBuf::try_from(entry.into_path()).map_err(|error| {
+            let path = error.as_path().to_string_lossy().into_owned();
+            FixtureError::new(FixtureKind::Walk)
+                .with_path(path)
+                .with_source(error.into_io_error())
+        })?;
+        paths.push(ChildPath::new(path));
+    }
+    paths.sort();
+    Ok(paths)
+}
+
 #[cfg(windows)]
 fn symlink_to_file(link: &Utf8Path, target: &Path) -> Result<(), FixtureError> {
     std::os::windows::fs::symlink_file(target, link)
@@ -374,4 +1314,222 @@ mod tests {
         assert!(link.is_symlink());
         assert_eq!(link.read_link_utf8().unwrap(), file);
     }
+
+    #[test]
+    fn test_hardlink_to() {
+        let temp_dir = Utf8TempDir::new().unwrap();
+        let file = temp_dir.child("file");
+        file.write_str("contents").unwrap();
+        let link = temp_dir.child("dir/link");
+        link.hardlink_to(&file).unwrap();
+
+        assert!(link.is_file());
+        assert!(!link.is_symlink());
+        assert_eq!(fs::read_to_string(&link).unwrap(), "contents");
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+
+            assert_eq!(
+                link.metadata().unwrap().ino(),
+                file.metadata().unwrap().ino()
+            );
+            assert_eq!(file.metadata().unwrap().nlink(), 2);
+        }
+
+        let error = temp_dir
+            .child("other")
+            .hardlink_to(temp_dir.path().join("missing"))
+            .unwrap_err();
+        assert_eq!(error.kind(), FixtureKind::Hardlink);
+    }
+
+    #[test]
+    fn test_write_template() {
+        let temp_dir = Utf8TempDir::new().unwrap();
+        let context = TemplateContext::new(&temp_dir)
+            .var("name", "value")
+            .var("tempdir", "ignored");
+        let file = temp_dir.child("dir/config.txt");
+        file.write_template(
+            "root={{tempdir}} data={{ child: data/x.db }} name={{name}} raw={{{{name}}",
+            &context,
+        )
+        .unwrap();
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            format!(
+                "root={} data={} name=value raw={{{{name}}}}",
+                temp_dir.path(),
+                temp_dir.path().join("data/x.db")
+            ),
+        );
+
+        for (template, message) in [
+            ("{{missing}}", "unknown placeholder `{{missing}}`"),
+            ("a {{tempdir", "unclosed placeholder at byte 2"),
+            (
+                "{{child:/abs}}",
+                "path in placeholder `{{child:/abs}}` is absolute",
+            ),
+            (
+                "{{child:../../etc}}",
+                "path in placeholder `{{child:../../etc}}` contains `..`",
+            ),
+        ] {
+            let error = file.write_template(template, &context).unwrap_err();
+            assert_eq!(error.kind(), FixtureKind::Template);
+            assert_eq!(
+                std::error::Error::source(&error).unwrap().to_string(),
+                message
+            );
+        }
+    }
+
+    #[test]
+    fn test_read() {
+        let temp_dir = Utf8TempDir::new().unwrap();
+        let file = temp_dir.child("file.txt");
+        file.write_str("one\r\ntwo\n").unwrap();
+        assert_eq!(file.read_str().unwrap(), "one\r\ntwo\n");
+        assert_eq!(file.read_bytes().unwrap(), b"one\r\ntwo\n");
+        assert_eq!(file.read_lines().unwrap(), ["one", "two"]);
+
+        let missing = temp_dir.child("missing.txt");
+        let error = missing.read_str().unwrap_err();
+        assert_eq!(error.kind(), FixtureKind::ReadFile);
+        assert_eq!(error.path(), Some(missing.as_path()));
+
+        file.write_binary(b"\xff").unwrap();
+        let error = file.read_lines().unwrap_err();
+        assert_eq!(error.kind(), FixtureKind::ReadFile);
+        assert_eq!(error.path(), Some(file.as_path()));
+        file.read_bytes().unwrap();
+
+        #[cfg(feature = "json")]
+        {
+            file.write_str("[1, 2]").unwrap();
+            assert_eq!(file.read_json::<Vec<u32>>().unwrap(), [1, 2]);
+            let error = file.read_json::<String>().unwrap_err();
+            assert_eq!(error.kind(), FixtureKind::ReadFile);
+            assert_eq!(error.path(), Some(file.as_path()));
+        }
+    }
+
+    #[test]
+    fn test_glob() {
+        let temp_dir = Utf8TempDir::new().unwrap();
+        temp_dir.child("a.txt").touch().unwrap();
+        temp_dir.child("b/c.txt").touch().unwrap();
+        temp_dir.child("b/d.json").touch().unwrap();
+        temp_dir.child("e/f.txt").touch().unwrap();
+        temp_dir
+            .child("link")
+            .symlink_to_dir(temp_dir.child("e"))
+            .unwrap();
+
+        let paths = temp_dir.glob(&["*.txt", "!b/"]).unwrap();
+        assert_eq!(paths, [temp_dir.child("a.txt"), temp_dir.child("e/f.txt")]);
+
+        let b = temp_dir.child("b");
+        assert_eq!(
+            b.glob(&["*"]).unwrap(),
+            [b.child("c.txt"), b.child("d.json")]
+        );
+
+        let dirs = temp_dir
+            .glob_with(&["*"], &GlobOptions::new().dirs_only())
+            .unwrap();
+        assert_eq!(dirs, [temp_dir.child("b"), temp_dir.child("e")]);
+
+        let options = GlobOptions::new().follow_links(true).files_only();
+        let files = temp_dir.glob_with(&["f.txt"], &options).unwrap();
+        assert_eq!(
+            files,
+            [temp_dir.child("e/f.txt"), temp_dir.child("link/f.txt")]
+        );
+
+        let error = temp_dir.glob(&["a["]).unwrap_err();
+        assert_eq!(error.kind(), FixtureKind::Walk);
+    }
+
+    #[test]
+    fn test_set_times() {
+        let temp_dir = Utf8TempDir::new().unwrap();
+        let file = temp_dir.child("file");
+        file.touch().unwrap();
+        let modified = || file.metadata().unwrap().modified().unwrap();
+        let accessed = || file.metadata().unwrap().accessed().unwrap();
+
+        let epoch = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        file.set_times(epoch, epoch + Duration::from_secs(60))
+            .unwrap();
+        assert_eq!(accessed(), epoch);
+        assert_eq!(modified(), epoch + Duration::from_secs(60));
+
+        // Setting one time leaves the other alone.
+        file.set_accessed(epoch - Duration::from_secs(60)).unwrap();
+        assert_eq!(accessed(), epoch - Duration::from_secs(60));
+        assert_eq!(modified(), epoch + Duration::from_secs(60));
+
+        // Times relative to the file's own modification time use the original
+        // value.
+        let relative = FixtureTime::modified_of(file.as_path());
+        file.set_times(relative.clone().plus(Duration::from_secs(1)), relative)
+            .unwrap();
+        assert_eq!(accessed(), epoch + Duration::from_secs(61));
+        assert_eq!(modified(), epoch + Duration::from_secs(60));
+
+        let before = SystemTime::now();
+        temp_dir
+            .set_modified(FixtureTime::now().minus(Duration::from_secs(3600)))
+            .unwrap();
+        let dir_modified = temp_dir.path().metadata().unwrap().modified().unwrap();
+        assert!(dir_modified >= before - Duration::from_secs(3600));
+        assert!(dir_modified < before);
+
+        let error = file
+            .set_modified(FixtureTime::modified_of(temp_dir.path().join("missing")))
+            .unwrap_err();
+        assert_eq!(error.kind(), FixtureKind::SetTimes);
+    }
+
+    #[test]
+    fn test_set_permissions() {
+        let temp_dir = Utf8TempDir::new().unwrap();
+        let file = temp_dir.child("dir/sub/file");
+        file.touch().unwrap();
+        let readonly = |path: &Utf8Path| path.metadata().unwrap().permissions().readonly();
+
+        file.make_read_only().unwrap();
+        assert!(readonly(&file));
+        file.make_writable().unwrap();
+        assert!(!readonly(&file));
+
+        temp_dir.child("dir").make_read_only_recursive().unwrap();
+        assert!(readonly(&file));
+        assert!(readonly(temp_dir.child("dir/sub").as_path()));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let mode = |path: &Utf8Path| path.metadata().unwrap().permissions().mode() & 0o777;
+            let script = temp_dir.child("script.sh");
+            script.touch().unwrap();
+            script.set_mode(0o640).unwrap();
+            assert_eq!(mode(&script), 0o640);
+            script.make_executable().unwrap();
+            assert_eq!(mode(&script), 0o750);
+        }
+
+        let error = temp_dir.child("missing").make_read_only().unwrap_err();
+        assert_eq!(error.kind(), FixtureKind::SetPermissions);
+
+        // Cleanup restores write permissions as needed.
+        let path = temp_dir.path().to_owned();
+        temp_dir.close().unwrap();
+        assert!(!path.exists());
+    }
 }
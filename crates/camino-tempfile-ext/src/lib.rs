@@ -34,8 +34,12 @@
 //!
 //! # Features
 //!
+//! - **archive**: Enable populating directories from tar, tar.gz and zip archives, and exporting them as tar.gz archives. *Not enabled by default.*
 //! - **assert**: Enable assertions on file and directory contents. *Not enabled by default.*
 //! - **assert-color**: Enable colored output for assertions: enables **assert**. *Not enabled by default.*
+//! - **json**: Enable writing and reading values serialized as JSON with [serde](https://serde.rs). *Not enabled by default.*
+//! - **toml**: Enable writing values serialized as TOML with [serde](https://serde.rs). *Not enabled by default.*
+//! - **yaml**: Enable writing values serialized as YAML with [serde](https://serde.rs). *Not enabled by default.*
 //!
 //! # Minimum supported Rust version (MSRV)
 //!
@@ -65,10 +69,19 @@ pub mod fixture;
 pub mod prelude {
     #[cfg(feature = "assert")]
     pub use crate::assert::PathAssert;
+    #[cfg(feature = "json")]
+    pub use crate::fixture::FileWriteJson;
+    #[cfg(feature = "toml")]
+    pub use crate::fixture::FileWriteToml;
+    #[cfg(feature = "yaml")]
+    pub use crate::fixture::FileWriteYaml;
     pub use crate::fixture::{
-        FileTouch, FileWriteBin, FileWriteFile, FileWriteStr, PathChild, PathCopy, PathCreateDir,
-        SymlinkToDir, SymlinkToFile,
+        FileRead, FileSetTimes, FileTouch, FileWriteBin, FileWriteFile, FileWriteStr,
+        FileWriteTemplate, HardlinkTo, PathChild, PathCopy, PathCreateDir, PathGlob,
+        PathSetPermissions, SymlinkToDir, SymlinkToFile,
     };
+    #[cfg(feature = "archive")]
+    pub use crate::fixture::{PathExport, PathExtract};
     pub use camino_tempfile::{NamedUtf8TempFile, Utf8TempDir};
 }
 
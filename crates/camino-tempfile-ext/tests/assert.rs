@@ -5,7 +5,7 @@
 
 #![cfg(feature = "assert")]
 
-use camino_tempfile_ext::{fixture::ChildPath, prelude::*};
+use camino_tempfile_ext::{assert::DirContentsPredicate, fixture::ChildPath, prelude::*};
 use predicates::prelude::*;
 
 #[test]
@@ -28,3 +28,47 @@ fn verify_failure_output() {
     let f = ChildPath::new("Cargo.toml");
     f.assert("Not real content");
 }
+
+#[test]
+fn dir_contents() {
+    let temp = Utf8TempDir::new().unwrap();
+    temp.child("a.txt").touch().unwrap();
+    temp.child("b/c.json").touch().unwrap();
+    temp.child("b/d/e.json").touch().unwrap();
+
+    temp.assert(DirContentsPredicate::exactly(["a.txt", "b/**/*.json"]));
+    temp.assert(DirContentsPredicate::exactly(["*", "b/*", "b/d/*"]));
+    temp.assert(DirContentsPredicate::at_least(["b/c.json"]));
+    temp.assert(DirContentsPredicate::none_of(["*.json", "missing"]));
+
+    let pred = DirContentsPredicate::exactly(["a.txt", "b/*.json", "f.txt"]);
+    let case = pred.find_case(false, temp.path()).unwrap();
+    let products: Vec<_> = case
+        .products()
+        .map(|product| format!("{}: {}", product.name(), product.value()))
+        .collect();
+    assert_eq!(
+        products,
+        [
+            "missing: f.txt",
+            "unexpected: b/d",
+            "unexpected: b/d/e.json"
+        ]
+    );
+
+    let pred = DirContentsPredicate::none_of(["**/*.json"]);
+    let case = pred.find_case(false, temp.path()).unwrap();
+    let products: Vec<_> = case
+        .products()
+        .map(|product| format!("{}: {}", product.name(), product.value()))
+        .collect();
+    assert_eq!(products, ["unexpected: b/c.json", "unexpected: b/d/e.json"]);
+}
+
+#[test]
+#[should_panic(expected = "missing: f.txt")]
+fn dir_contents_failure_output() {
+    let temp = Utf8TempDir::new().unwrap();
+    temp.child("a.txt").touch().unwrap();
+    temp.assert(DirContentsPredicate::at_least(["a.txt", "f.txt"]));
+}
@@ -0,0 +1,140 @@
+// Copyright (c) The camino-tempfile Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::{Builder, helpers, trace};
+use camino::{Utf8Path, Utf8PathBuf};
+use std::{
+    fs,
+    sync::{Condvar, Mutex, OnceLock, mpsc},
+    thread,
+};
+
+/// Wait for all pending background deletions of temporary directories to
+/// finish.
+///
+/// Temporary directories deleted with [`Utf8TempDir::close_in_background`], or
+/// created with [`Builder::background_cleanup`] set, are removed by a
+/// background thread. Background threads are not waited on when the process
+/// exits, so directories that are still being deleted may be left behind. Call
+/// this function before exiting (for example, at the end of `main`) to ensure
+/// that all deletions have completed.
+///
+/// This function returns immediately if no deletions are pending.
+///
+/// # Examples
+///
+/// ```
+/// use camino_tempfile::{Utf8TempDir, wait_for_background_cleanup};
+///
+/// # fn main() -> std::io::Result<()> {
+/// let dir = Utf8TempDir::new()?;
+/// let path = dir.path().to_owned();
+/// dir.close_in_background();
+///
+/// wait_for_background_cleanup();
+/// assert!(!path.exists());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Utf8TempDir::close_in_background`]: crate::Utf8TempDir::close_in_background
+pub fn wait_for_background_cleanup() {
+    if let Some(cleaner) = CLEANER.get() {
+        cleaner.wait();
+    }
+}
+
+/// Removes the directory at `path` in the background.
+///
+/// The directory is first renamed to a unique name in the same parent
+/// directory, so that it disappears from its original location immediately.
+pub(crate) fn remove_dir_all_in_background(path: &Utf8Path) {
+    let trash_path = match rename_to_trash(path) {
+        Ok(trash_path) => {
+            trace::background_queued(path, &trash_path, None);
+            trash_path
+        }
+        Err(error) => {
+            trace::background_queued(path, path, Some(&error));
+            path.to_owned()
+        }
+    };
+    CLEANER.get_or_init(Cleaner::spawn).send(trash_path);
+}
+
+fn rename_to_trash(path: &Utf8Path) -> std::io::Result<Utf8PathBuf> {
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_str().is_empty() => parent,
+        _ => Utf8Path::new("."),
+    };
+    let prefix = format!(".{}.trash-", path.file_name().unwrap_or("tmp"));
+
+    // Use `make_in` to pick a unique name. Cleanup is disabled, since the
+    // returned path is removed by the background thread.
+    let trash = Builder::new()
+        .prefix(&prefix)
+        .disable_cleanup(true)
+        .make_in(parent, |trash_path| fs::rename(path, trash_path))?;
+    Ok(trash.path().to_owned())
+}
+
+static CLEANER: OnceLock<Cleaner> = OnceLock::new();
+
+struct Cleaner {
+    sender: mpsc::Sender<Utf8PathBuf>,
+    pending: Mutex<usize>,
+    done: Condvar,
+}
+
+impl Cleaner {
+    fn spawn() -> Self {
+        let (sender, receiver) = mpsc::channel::<Utf8PathBuf>();
+        // If the thread can't be spawned, the receiver is dropped along with
+        // the closure, and `send` falls back to deleting directories inline.
+        let _ = thread::Builder::new()
+            .name("camino-tempfile-cleanup".to_owned())
+            .spawn(move || {
+                for path in receiver {
+                    // Errors are not returned anywhere, just like in the
+                    // `Utf8TempDir` destructor.
+                    let result = helpers::remove_dir_all(&path);
+                    trace::background_cleanup(&path, &result);
+                    CLEANER
+                        .get()
+                        .expect("cleaner is initialized before sending paths")
+                        .finish_one();
+                }
+            });
+        Self {
+            sender,
+            pending: Mutex::new(0),
+            done: Condvar::new(),
+        }
+    }
+
+    fn send(&self, path: Utf8PathBuf) {
+        *self.pending.lock().expect("pending lock poisoned") += 1;
+        if let Err(error) = self.sender.send(path) {
+            // The background thread is gone or was never spawned: delete the
+            // directory inline.
+            let result = helpers::remove_dir_all(&error.0);
+            trace::background_cleanup(&error.0, &result);
+            self.finish_one();
+        }
+    }
+
+    fn finish_one(&self) {
+        let mut pending = self.pending.lock().expect("pending lock poisoned");
+        *pending -= 1;
+        if *pending == 0 {
+            self.done.notify_all();
+        }
+    }
+
+    fn wait(&self) {
+        let mut pending = self.pending.lock().expect("pending lock poisoned");
+        while *pending > 0 {
+            pending = self.done.wait(pending).expect("pending lock poisoned");
+        }
+    }
+}
@@ -1,14 +1,43 @@
 // Copyright (c) The camino-tempfile Contributors
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use crate::{NamedUtf8TempFile, Utf8TempDir, helpers::utf8_env_temp_dir};
-use camino::{Utf8Path, Utf8PathBuf};
-use std::{convert::TryFrom, io};
+#[cfg(unix)]
+use crate::process_shared::ProcessLock;
+use crate::{
+    NamedUtf8TempFile, Utf8TempDir, Utf8TempDirPool, Utf8TempOperation,
+    errors::{IoResultExt, non_utf8_error},
+    helpers::utf8_env_temp_dir,
+    trace::{self, EntryKind},
+};
+#[cfg(unix)]
+use crate::{Utf8TempFifo, fifo::mkfifo};
+#[cfg(unix)]
+use crate::{
+    Utf8TempPath,
+    socket::{check_socket_dir, socket_dir},
+};
+use camino::Utf8Path;
+use std::{fs, io};
 
 /// Create a new temporary file or directory with custom parameters.
 #[derive(Debug, Clone, Default, Eq, PartialEq)]
 pub struct Builder<'a, 'b> {
-    inner: tempfile::Builder<'a, 'b>,
+    // The options are stored here rather than in a `tempfile::Builder`, so that
+    // a builder with different prefix and suffix lifetimes can be produced from
+    // them (see `Builder::tempfile_sibling_of`).
+    prefix: Option<&'a str>,
+    suffix: Option<&'b str>,
+    rand_bytes: Option<usize>,
+    append: bool,
+    permissions: Option<fs::Permissions>,
+    disable_cleanup: bool,
+    background_cleanup: bool,
+    #[cfg(unix)]
+    process_shared: bool,
+    #[cfg(unix)]
+    exact_permissions: Option<u32>,
+    #[cfg(target_os = "linux")]
+    xattrs: Vec<(String, Vec<u8>)>,
 }
 
 impl<'a, 'b> Builder<'a, 'b> {
@@ -113,7 +142,7 @@ impl<'a, 'b> Builder<'a, 'b> {
     /// # }
     /// ```
     pub fn prefix<S: AsRef<str> + ?Sized>(&mut self, prefix: &'a S) -> &mut Self {
-        self.inner.prefix(prefix.as_ref());
+        self.prefix = Some(prefix.as_ref());
         self
     }
 
@@ -138,7 +167,7 @@ impl<'a, 'b> Builder<'a, 'b> {
     /// # }
     /// ```
     pub fn suffix<S: AsRef<str> + ?Sized>(&mut self, suffix: &'b S) -> &mut Self {
-        self.inner.suffix(suffix.as_ref());
+        self.suffix = Some(suffix.as_ref());
         self
     }
 
@@ -162,7 +191,7 @@ impl<'a, 'b> Builder<'a, 'b> {
     /// # }
     /// ```
     pub fn rand_bytes(&mut self, rand: usize) -> &mut Self {
-        self.inner.rand_bytes(rand);
+        self.rand_bytes = Some(rand);
         self
     }
 
@@ -186,7 +215,7 @@ impl<'a, 'b> Builder<'a, 'b> {
     /// # }
     /// ```
     pub fn append(&mut self, append: bool) -> &mut Self {
-        self.inner.append(append);
+        self.append = append;
         self
     }
 
@@ -212,6 +241,9 @@ impl<'a, 'b> Builder<'a, 'b> {
     /// combined with the standard umask `0o022`, the defaults yield `0o600` for
     /// tempfiles and `0o755` for tempdirs.
     ///
+    /// To get exactly the requested permission bits regardless of the `umask`,
+    /// use [`Builder::exact_permissions`] instead.
+    ///
     /// ## Windows and others
     ///
     /// This setting is unsupported and trying to set a file or directory
@@ -258,8 +290,64 @@ impl<'a, 'b> Builder<'a, 'b> {
     /// # }
     /// # Ok::<(), std::io::Error>(())
     /// ```
-    pub fn permissions(&mut self, permissions: std::fs::Permissions) -> &mut Self {
-        self.inner.permissions(permissions);
+    pub fn permissions(&mut self, permissions: fs::Permissions) -> &mut Self {
+        self.permissions = Some(permissions);
+        self
+    }
+
+    /// Set the exact mode of created temporary files and directories,
+    /// regardless of the `umask`.
+    ///
+    /// Temporary files, directories and named pipes are created accessible
+    /// only to their owner, and are then changed to exactly `mode` with
+    /// `chmod(2)` before they're returned. This also applies to entries
+    /// created with [`Builder::make`], other than symbolic links, which are
+    /// created however the closure creates them.
+    ///
+    /// This takes priority over [`Builder::permissions`].
+    ///
+    /// # Security
+    ///
+    /// As with [`Builder::permissions`], this method allows widening the
+    /// permissions of temporary entries, which reduces security.
+    ///
+    /// Because the mode is changed after the entry is created, it's possible for
+    /// other users to observe the entry with narrower permissions than `mode`,
+    /// but never with wider ones.
+    ///
+    /// For temporary files and directories, the mode is changed through an open
+    /// handle to the entry that was created. Entries created with
+    /// [`Builder::make`], including named pipes, have no such handle, so their
+    /// mode is changed by path after checking that they aren't symbolic links.
+    /// If other users can rename entries in the directory, they could replace
+    /// such an entry in between, and have the mode applied to a different file.
+    /// Shared directories like `/tmp` normally have the sticky bit set, which
+    /// prevents this.
+    ///
+    /// This method is only available on Unix.
+    ///
+    /// # Errors
+    ///
+    /// Creating an entry fails if its mode can't be changed. The entry is
+    /// removed in that case, unless cleanup is disabled.
+    ///
+    /// # Examples
+    ///
+    /// Create a temporary file that is group-writable, even with the standard
+    /// umask `0o022`.
+    ///
+    /// ```
+    /// use camino_tempfile::Builder;
+    /// use std::os::unix::fs::PermissionsExt;
+    ///
+    /// let tempfile = Builder::new().exact_permissions(0o664).tempfile()?;
+    /// let actual_permissions = tempfile.path().metadata()?.permissions();
+    /// assert_eq!(actual_permissions.mode() & 0o7777, 0o664);
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    #[cfg(unix)]
+    pub fn exact_permissions(&mut self, mode: u32) -> &mut Self {
+        self.exact_permissions = Some(mode);
         self
     }
 
@@ -291,7 +379,140 @@ impl<'a, 'b> Builder<'a, 'b> {
     /// # Ok::<(), std::io::Error>(())
     /// ```
     pub fn disable_cleanup(&mut self, disable_cleanup: bool) -> &mut Self {
-        self.inner.disable_cleanup(disable_cleanup);
+        self.disable_cleanup = disable_cleanup;
+        self
+    }
+
+    /// Remove temporary directories on a background thread when the
+    /// [`Utf8TempDir`] goes out of scope.
+    ///
+    /// By default, dropping a [`Utf8TempDir`] deletes the directory and its
+    /// contents on the current thread, which can take a long time for large
+    /// directories. When `background_cleanup` is set to `true`, dropping the
+    /// `Utf8TempDir` behaves like [`Utf8TempDir::close_in_background`]
+    /// instead: the directory is renamed out of the way immediately, and then
+    /// deleted on a background thread.
+    ///
+    /// This option only affects temporary directories, and has no effect if
+    /// cleanup is disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use camino_tempfile::{Builder, wait_for_background_cleanup};
+    ///
+    /// let tempdir = Builder::new().background_cleanup(true).tempdir()?;
+    /// let path = tempdir.path().to_owned();
+    ///
+    /// drop(tempdir);
+    /// assert!(!path.exists());
+    ///
+    /// wait_for_background_cleanup();
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    pub fn background_cleanup(&mut self, background_cleanup: bool) -> &mut Self {
+        self.background_cleanup = background_cleanup;
+        self
+    }
+
+    /// Allow temporary directories to be shared with other processes.
+    ///
+    /// When `process_shared` is set to `true`, temporary directories are
+    /// created with a marker file named
+    /// [`PROCESS_LOCK_FILE_NAME`](crate::PROCESS_LOCK_FILE_NAME) inside them,
+    /// on which the [`Utf8TempDir`] holds a shared `flock(2)`. Other processes
+    /// can then join the directory by path with
+    /// [`JoinedUtf8TempDir::join`](crate::JoinedUtf8TempDir::join), which
+    /// takes a shared lock of its own.
+    ///
+    /// When the `Utf8TempDir` or a `JoinedUtf8TempDir` goes out of scope, its
+    /// lock is released. The last process to release its lock removes the
+    /// directory; the others leave it in place.
+    ///
+    /// This option only affects temporary directories. If cleanup is disabled,
+    /// this process's lock is released without removing the directory, but the
+    /// last process to release its lock will still remove it.
+    ///
+    /// This method is only available on Unix.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use camino_tempfile::{Builder, JoinedUtf8TempDir};
+    ///
+    /// let tempdir = Builder::new().process_shared(true).tempdir()?;
+    ///
+    /// // This would typically happen in another process.
+    /// let joined = JoinedUtf8TempDir::join(tempdir.path())?;
+    ///
+    /// let path = tempdir.path().to_owned();
+    /// drop(tempdir);
+    /// assert!(path.exists(), "the directory is still in use");
+    ///
+    /// drop(joined);
+    /// assert!(!path.exists(), "the last user removed the directory");
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    #[cfg(unix)]
+    pub fn process_shared(&mut self, process_shared: bool) -> &mut Self {
+        self.process_shared = process_shared;
+        self
+    }
+
+    /// Set an extended attribute on created temporary files and directories.
+    ///
+    /// Extended attributes are set right after the entry is created, before it
+    /// is returned. This means that they are in place before the file is made
+    /// visible under its final name with [`NamedUtf8TempFile::persist`].
+    ///
+    /// This method can be called multiple times to set several attributes. If
+    /// it's called more than once with the same `name`, the last value wins.
+    ///
+    /// Unprivileged processes can only set attributes in the `user.` namespace,
+    /// and only on regular files and directories. Setting attributes on other
+    /// kinds of entries created with [`Builder::make`] fails.
+    ///
+    /// Attributes are set before the mode is changed with
+    /// [`Builder::exact_permissions`], so they can be set even if that mode
+    /// doesn't allow writing.
+    ///
+    /// This method is only available on Linux.
+    ///
+    /// # Errors
+    ///
+    /// Creating an entry fails if any of its extended attributes can't be set.
+    /// If the filesystem doesn't support user extended attributes, the error
+    /// has kind [`io::ErrorKind::Unsupported`]. The entry is removed in that
+    /// case, unless cleanup is disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use camino_tempfile::Builder;
+    /// use std::io;
+    ///
+    /// let file = match Builder::new()
+    ///     .xattr("user.build-cache.key", "abc123")
+    ///     .tempfile()
+    /// {
+    ///     Ok(file) => file,
+    ///     // Some filesystems, such as older versions of tmpfs, don't support
+    ///     // user extended attributes.
+    ///     Err(error) if error.kind() == io::ErrorKind::Unsupported => return Ok(()),
+    ///     Err(error) => return Err(error),
+    /// };
+    ///
+    /// let xattrs = file.xattrs()?;
+    /// assert!(xattrs.contains(&("user.build-cache.key".to_owned(), b"abc123".to_vec())));
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    #[cfg(target_os = "linux")]
+    pub fn xattr<V: AsRef<[u8]>>(&mut self, name: &str, value: V) -> &mut Self {
+        let value = value.as_ref().to_vec();
+        match self.xattrs.iter_mut().find(|(n, _)| n == name) {
+            Some((_, v)) => *v = value,
+            None => self.xattrs.push((name.to_owned(), value)),
+        }
         self
     }
 
@@ -364,8 +585,180 @@ impl<'a, 'b> Builder<'a, 'b> {
     /// [security]: struct.NamedUtf8TempFile.html#security
     /// [resource-leaking]: struct.NamedUtf8TempFile.html#resource-leaking
     pub fn tempfile_in<P: AsRef<Utf8Path>>(&self, dir: P) -> io::Result<NamedUtf8TempFile> {
-        let temp_file = self.inner.tempfile_in(dir.as_ref())?;
-        NamedUtf8TempFile::from_temp_file(temp_file)
+        let dir = dir.as_ref();
+        let result = self
+            .inner(EntryKind::File)
+            .tempfile_in(dir)
+            .with_tempfile_err_op(Utf8TempOperation::CreateFile, || dir)
+            .and_then(|temp_file| {
+                let file = NamedUtf8TempFile::from_temp_file(temp_file, self.disable_cleanup)?;
+                self.after_create(file.path(), Created::File(file.as_file()))?;
+                Ok(file)
+            });
+        trace::create(EntryKind::File, dir, &result, |file| file.path());
+        result
+    }
+
+    /// Create the named temporary file in the same directory as `target`.
+    ///
+    /// This is useful for writing out a file and then atomically replacing
+    /// `target` with it via [`NamedUtf8TempFile::persist`]: since the temporary
+    /// file is created next to `target`, the two are always on the same
+    /// filesystem.
+    ///
+    /// The directory is the parent of `target`, or the current directory if
+    /// `target` is a bare relative file name like `foo.txt`. `target` itself
+    /// does not need to exist.
+    ///
+    /// Unless overridden with [`Builder::prefix`] and [`Builder::suffix`], the
+    /// prefix defaults to `.{file_name}.` and the suffix defaults to the
+    /// extension of `target` (including the leading dot), if any. For example,
+    /// with a target of `dir/foo.txt`, the temporary file will be named
+    /// something like `dir/.foo.txt.a1B2c3.txt`.
+    ///
+    /// # Security
+    ///
+    /// See [the security][security] docs on `NamedUtf8TempFile`.
+    ///
+    /// # Resource leaking
+    ///
+    /// See [the resource leaking][resource-leaking] docs on `NamedUtf8TempFile`.
+    ///
+    /// # Errors
+    ///
+    /// If `target` does not have a file name (for example, if it is `/` or ends
+    /// in `..`), an error of kind [`io::ErrorKind::InvalidInput`] is returned.
+    /// If the file cannot be created, `Err` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::io::{self, Write};
+    /// # fn main() {
+    /// #     if let Err(_) = run() {
+    /// #         ::std::process::exit(1);
+    /// #     }
+    /// # }
+    /// # fn run() -> Result<(), io::Error> {
+    /// use camino_tempfile::{Builder, tempdir};
+    ///
+    /// let dir = tempdir()?;
+    /// let target = dir.path().join("config.toml");
+    ///
+    /// let mut tempfile = Builder::new().tempfile_sibling_of(&target)?;
+    /// assert_eq!(tempfile.path().parent(), Some(dir.path()));
+    ///
+    /// let name = tempfile.path().file_name().unwrap();
+    /// assert!(name.starts_with(".config.toml."));
+    /// assert!(name.ends_with(".toml"));
+    ///
+    /// writeln!(tempfile, "key = \"value\"")?;
+    /// tempfile.persist(&target)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [security]: struct.NamedUtf8TempFile.html#security
+    /// [resource-leaking]: struct.NamedUtf8TempFile.html#resource-leaking
+    pub fn tempfile_sibling_of<P: AsRef<Utf8Path>>(
+        &self,
+        target: P,
+    ) -> io::Result<NamedUtf8TempFile> {
+        let target = target.as_ref();
+        let file_name = target.file_name().ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("target path {target} does not have a file name"),
+            )
+        })?;
+        let dir = match target.parent() {
+            Some(parent) if !parent.as_str().is_empty() => parent,
+            _ => Utf8Path::new("."),
+        };
+
+        let default_prefix = format!(".{file_name}.");
+        let default_suffix = target
+            .extension()
+            .map(|extension| format!(".{extension}"))
+            .unwrap_or_default();
+
+        let result = self
+            .inner_with(
+                self.prefix.unwrap_or(&default_prefix),
+                self.suffix.unwrap_or(&default_suffix),
+            )
+            .tempfile_in(dir)
+            .with_tempfile_err_op(Utf8TempOperation::CreateFile, || dir)
+            .and_then(|temp_file| {
+                let file = NamedUtf8TempFile::from_temp_file(temp_file, self.disable_cleanup)?;
+                self.after_create(file.path(), Created::File(file.as_file()))?;
+                Ok(file)
+            });
+        trace::create(EntryKind::File, dir, &result, |file| file.path());
+        result
+    }
+
+    /// Create a named pipe (FIFO) inside of [`std::env::temp_dir()`], using this builder's
+    /// prefix, suffix, number of random bytes, and permissions.
+    ///
+    /// The FIFO is deleted when the returned [`Utf8TempFifo`] is dropped. See [`Utf8TempFifo`]
+    /// for how to open its ends without deadlocking.
+    ///
+    /// If permissions are not set with [`Builder::permissions`], the FIFO is created with mode
+    /// `0o600`. As with `mkfifo(3)`, the mode is modified by the process's umask.
+    ///
+    /// This method is only available on Unix.
+    ///
+    /// # Errors
+    ///
+    /// If the FIFO can not be created, or if [`std::env::temp_dir()`] is non-UTF-8, `Err` is
+    /// returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use camino_tempfile::Builder;
+    ///
+    /// # fn main() -> std::io::Result<()> {
+    /// let fifo = Builder::new().prefix("my-fifo").fifo()?;
+    /// assert!(fifo.path().file_name().unwrap().starts_with("my-fifo"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(unix)]
+    pub fn fifo(&self) -> io::Result<Utf8TempFifo> {
+        self.fifo_in(utf8_env_temp_dir()?)
+    }
+
+    /// Create a named pipe (FIFO) inside of `dir`, using this builder's prefix, suffix, number
+    /// of random bytes, and permissions.
+    ///
+    /// See [`Builder::fifo`] for more.
+    ///
+    /// # Errors
+    ///
+    /// If the FIFO can not be created, `Err` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use camino_tempfile::Builder;
+    ///
+    /// # fn main() -> std::io::Result<()> {
+    /// let fifo = Builder::new().fifo_in("./")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(unix)]
+    pub fn fifo_in<P: AsRef<Utf8Path>>(&self, dir: P) -> io::Result<Utf8TempFifo> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = self
+            .creation_permissions(EntryKind::File)
+            .map_or(0o600, |permissions| permissions.mode());
+        let temp_file = self.make_in(dir, |path| mkfifo(path, mode))?;
+        let ((), path) = temp_file.into_parts();
+        Ok(Utf8TempFifo::new(path))
     }
 
     /// Attempts to make a temporary directory inside of [`std::env::temp_dir()`] whose name will
@@ -429,8 +822,52 @@ impl<'a, 'b> Builder<'a, 'b> {
     ///
     /// [resource-leaking]: struct.Utf8TempDir.html#resource-leaking
     pub fn tempdir_in<P: AsRef<Utf8Path>>(&self, dir: P) -> io::Result<Utf8TempDir> {
-        let temp_dir = self.inner.tempdir_in(dir.as_ref())?;
-        Utf8TempDir::from_temp_dir(temp_dir)
+        let dir = dir.as_ref();
+        let result = self
+            .inner(EntryKind::Dir)
+            .tempdir_in(dir)
+            .with_tempfile_err_op(Utf8TempOperation::CreateDir, || dir)
+            .and_then(|temp_dir| {
+                let mut temp_dir = Utf8TempDir::from_temp_dir(temp_dir)?;
+                temp_dir.set_cleanup_options(self.disable_cleanup, self.background_cleanup);
+                self.after_create(temp_dir.path(), Created::Dir)?;
+                #[cfg(unix)]
+                if self.process_shared {
+                    temp_dir.set_process_lock(ProcessLock::create(temp_dir.path())?);
+                }
+                Ok(temp_dir)
+            });
+        trace::create(EntryKind::Dir, dir, &result, |dir| dir.path());
+        result
+    }
+
+    /// Create a [`Utf8TempDirPool`] whose directories are created inside of
+    /// [`std::env::temp_dir()`] with this builder's options.
+    ///
+    /// # Errors
+    ///
+    /// If [`std::env::temp_dir()`] is non-UTF-8, `Err` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use camino_tempfile::Builder;
+    ///
+    /// # fn main() -> std::io::Result<()> {
+    /// let pool = Builder::new().prefix("bench-").tempdir_pool()?;
+    /// let lease = pool.lease()?;
+    /// assert!(lease.path().file_name().unwrap().starts_with("bench-"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn tempdir_pool(&self) -> io::Result<Utf8TempDirPool<'a, 'b>> {
+        Ok(self.tempdir_pool_in(utf8_env_temp_dir()?))
+    }
+
+    /// Create a [`Utf8TempDirPool`] whose directories are created inside of
+    /// `dir` with this builder's options.
+    pub fn tempdir_pool_in<P: AsRef<Utf8Path>>(&self, dir: P) -> Utf8TempDirPool<'a, 'b> {
+        Utf8TempDirPool::from_builder(self.clone(), dir.as_ref().to_owned())
     }
 
     /// Attempts to create a temporary file (or file-like object) using the
@@ -505,6 +942,9 @@ impl<'a, 'b> Builder<'a, 'b> {
     /// to avoid these problems. This function is meant to enable more exotic
     /// use-cases.
     ///
+    /// For Unix domain sockets, prefer [`Builder::make_socket`], which makes
+    /// sure that the socket path is short enough to bind to.
+    ///
     /// # Resource leaking
     ///
     /// See [the resource leaking][resource-leaking] docs on `NamedUtf8TempFile`.
@@ -570,12 +1010,309 @@ impl<'a, 'b> Builder<'a, 'b> {
         F: FnMut(&Utf8Path) -> io::Result<R>,
         P: AsRef<Utf8Path>,
     {
-        let temp_file = self.inner.make_in(dir.as_ref(), |path| {
-            // This produces a better error message.
-            let utf8_path =
-                Utf8PathBuf::try_from(path.to_path_buf()).map_err(|error| error.into_io_error())?;
-            f(&utf8_path)
+        let dir = dir.as_ref();
+        let result = self
+            .inner(EntryKind::File)
+            .make_in(dir, |path| {
+                let utf8_path = Utf8Path::from_path(path).ok_or_else(|| non_utf8_error(path))?;
+                f(utf8_path)
+            })
+            .with_err_op(Utf8TempOperation::CreateFile, || dir)
+            .and_then(|temp_file| {
+                let file = NamedUtf8TempFile::from_temp_file(temp_file, self.disable_cleanup)?;
+                self.after_create(file.path(), Created::Made)?;
+                Ok(file)
+            });
+        trace::create(EntryKind::File, dir, &result, |file| file.path());
+        result
+    }
+
+    /// Attempts to create a temporary Unix domain socket (or any other file) using the provided
+    /// closure, in a directory short enough for the socket path to fit in `sun_path`.
+    ///
+    /// Unix domain socket paths are limited to [`MAX_SOCKET_PATH_LEN`] bytes. If
+    /// [`std::env::temp_dir()`] is too deeply nested for the socket path to fit, as is common on
+    /// CI machines and on macOS, this method falls back to `$XDG_RUNTIME_DIR`, then to `/tmp`.
+    ///
+    /// Otherwise, this is the same as [`Builder::make`]. See [`Builder::make_socket_in`] to
+    /// create a socket in a specific directory.
+    ///
+    /// # Errors
+    ///
+    /// If none of the directories above are short enough, an error with kind
+    /// [`std::io::ErrorKind::InvalidInput`] wrapping a [`SocketPathTooLongError`] is returned.
+    /// The closure is not called in that case.
+    ///
+    /// If the closure returns any error besides [`std::io::ErrorKind::AlreadyExists`] or
+    /// [`std::io::ErrorKind::AddrInUse`], then `Err` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use camino_tempfile::Builder;
+    /// use std::os::unix::net::UnixListener;
+    ///
+    /// # fn main() -> std::io::Result<()> {
+    /// let tempsock = Builder::new().make_socket(|path| UnixListener::bind(path))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`MAX_SOCKET_PATH_LEN`]: crate::MAX_SOCKET_PATH_LEN
+    /// [`SocketPathTooLongError`]: crate::SocketPathTooLongError
+    #[cfg(unix)]
+    pub fn make_socket<F, R>(&self, f: F) -> io::Result<NamedUtf8TempFile<R>>
+    where
+        F: FnMut(&Utf8Path) -> io::Result<R>,
+    {
+        let dir = socket_dir(self.name_len())?;
+        self.make_in(dir, f)
+    }
+
+    /// Attempts to create a temporary Unix domain socket (or any other file) in `dir` using the
+    /// provided closure, checking up front that the socket path fits in `sun_path`.
+    ///
+    /// This is the same as [`Builder::make_in`], except that if the path would be longer than
+    /// [`MAX_SOCKET_PATH_LEN`] bytes, a descriptive error is returned rather than whatever error
+    /// the closure produces.
+    ///
+    /// # Errors
+    ///
+    /// If the socket path would be too long, an error with kind
+    /// [`std::io::ErrorKind::InvalidInput`] wrapping a [`SocketPathTooLongError`] is returned.
+    /// The closure is not called in that case.
+    ///
+    /// If the closure returns any error besides [`std::io::ErrorKind::AlreadyExists`] or
+    /// [`std::io::ErrorKind::AddrInUse`], then `Err` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use camino_tempfile::Builder;
+    /// use std::os::unix::net::UnixListener;
+    ///
+    /// # fn main() -> std::io::Result<()> {
+    /// let tempsock = Builder::new().make_socket_in("./", |path| UnixListener::bind(path))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`MAX_SOCKET_PATH_LEN`]: crate::MAX_SOCKET_PATH_LEN
+    /// [`SocketPathTooLongError`]: crate::SocketPathTooLongError
+    #[cfg(unix)]
+    pub fn make_socket_in<F, R, P>(&self, dir: P, f: F) -> io::Result<NamedUtf8TempFile<R>>
+    where
+        F: FnMut(&Utf8Path) -> io::Result<R>,
+        P: AsRef<Utf8Path>,
+    {
+        let dir = dir.as_ref();
+        check_socket_dir(dir, self.name_len())?;
+        self.make_in(dir, f)
+    }
+
+    /// Reserves a unique path for a temporary Unix domain socket, in a directory short enough for
+    /// the path to fit in `sun_path`.
+    ///
+    /// This is for binding sockets with APIs that can't be called from the closure passed to
+    /// [`Builder::make_socket`], such as the listeners of async runtimes. The directory is picked
+    /// as with [`Builder::make_socket`]; see [`Builder::socket_path_in`] to use a specific
+    /// directory.
+    ///
+    /// Nothing is created at the returned path, so that a socket can be bound to it. Once bound,
+    /// the socket is deleted when the returned [`Utf8TempPath`] is dropped.
+    ///
+    /// # Security
+    ///
+    /// The path is checked to be unused, but since nothing is created at it, another process could
+    /// create an entry there before the socket is bound. Binding then fails with
+    /// [`std::io::ErrorKind::AddrInUse`]. Where possible, prefer [`Builder::make_socket`], which
+    /// retries with another path in that case.
+    ///
+    /// # Errors
+    ///
+    /// If no directory is short enough, an error with kind [`std::io::ErrorKind::InvalidInput`]
+    /// wrapping a [`SocketPathTooLongError`] is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use camino_tempfile::Builder;
+    /// use std::os::unix::net::UnixListener;
+    ///
+    /// # fn main() -> std::io::Result<()> {
+    /// let path = Builder::new().socket_path()?;
+    /// let listener = UnixListener::bind(&path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`Utf8TempPath`]: crate::Utf8TempPath
+    /// [`SocketPathTooLongError`]: crate::SocketPathTooLongError
+    #[cfg(unix)]
+    pub fn socket_path(&self) -> io::Result<Utf8TempPath> {
+        let dir = socket_dir(self.name_len())?;
+        self.socket_path_in(dir)
+    }
+
+    /// Reserves a unique path for a temporary Unix domain socket in `dir`, checking up front that
+    /// the path fits in `sun_path`.
+    ///
+    /// See [`Builder::socket_path`] for more details and security implications.
+    ///
+    /// # Errors
+    ///
+    /// If the socket path would be too long, an error with kind
+    /// [`std::io::ErrorKind::InvalidInput`] wrapping a [`SocketPathTooLongError`] is returned.
+    ///
+    /// [`SocketPathTooLongError`]: crate::SocketPathTooLongError
+    #[cfg(unix)]
+    pub fn socket_path_in<P: AsRef<Utf8Path>>(&self, dir: P) -> io::Result<Utf8TempPath> {
+        let temp_file = self.make_socket_in(dir, |path| match fs::symlink_metadata(path) {
+            Ok(_) => Err(io::ErrorKind::AlreadyExists.into()),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(error) => Err(error),
         })?;
-        NamedUtf8TempFile::from_temp_file(temp_file)
+        let ((), path) = temp_file.into_parts();
+        Ok(path)
+    }
+
+    /// Returns the length, in bytes, of the file names this builder generates.
+    #[cfg(unix)]
+    fn name_len(&self) -> usize {
+        // These defaults match tempfile's.
+        self.prefix.unwrap_or(".tmp").len()
+            + self.rand_bytes.unwrap_or(6)
+            + self.suffix.unwrap_or("").len()
+    }
+
+    /// Applies options that can only be set once an entry has been created.
+    #[cfg_attr(not(unix), allow(unused_variables))]
+    fn after_create(&self, path: &Utf8Path, created: Created<'_>) -> io::Result<()> {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            // Use a handle to the entry where possible, so that the options
+            // apply to the entry that was created even if `path` has been
+            // replaced since. Directories are only opened if exact permissions
+            // are set, since they're created readable by their owner then.
+            let dir;
+            let handle = match created {
+                Created::File(file) => Some(file),
+                Created::Dir if self.exact_permissions.is_some() => {
+                    dir = open_dir(path).with_err_path(|| path)?;
+                    Some(&dir)
+                }
+                Created::Dir | Created::Made => None,
+            };
+
+            // Set extended attributes before changing the mode, which might
+            // remove write access to the entry.
+            #[cfg(target_os = "linux")]
+            for (name, value) in &self.xattrs {
+                match handle {
+                    Some(file) => crate::xattr::fset(file, name, value).with_err_path(|| path)?,
+                    None => crate::xattr::set(path, name, value)?,
+                }
+            }
+
+            if let Some(mode) = self.exact_permissions {
+                let permissions = fs::Permissions::from_mode(mode);
+                match handle {
+                    Some(file) => file.set_permissions(permissions).with_err_path(|| path)?,
+                    // Don't follow symbolic links created with `make`, and skip
+                    // closures passed to `make` that don't create anything.
+                    None => match fs::symlink_metadata(path) {
+                        Ok(metadata) if metadata.file_type().is_symlink() => {}
+                        Ok(_) => fs::set_permissions(path, permissions).with_err_path(|| path)?,
+                        Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+                        Err(error) => return Err(error).with_err_path(|| path),
+                    },
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the permissions to create entries of the given kind with, if
+    /// any.
+    #[cfg_attr(not(unix), allow(unused_variables))]
+    fn creation_permissions(&self, kind: EntryKind) -> Option<fs::Permissions> {
+        // With exact permissions, create entries accessible only to their
+        // owner, so that they can still be opened and modified before their
+        // mode is changed in `after_create`.
+        #[cfg(unix)]
+        if self.exact_permissions.is_some() {
+            use std::os::unix::fs::PermissionsExt;
+
+            let mode = match kind {
+                EntryKind::File => 0o600,
+                EntryKind::Dir => 0o700,
+            };
+            return Some(fs::Permissions::from_mode(mode));
+        }
+        self.permissions.clone()
+    }
+
+    fn inner(&self, kind: EntryKind) -> tempfile::Builder<'a, 'b> {
+        let mut inner = tempfile::Builder::new();
+        if let Some(prefix) = self.prefix {
+            inner.prefix(prefix);
+        }
+        if let Some(suffix) = self.suffix {
+            inner.suffix(suffix);
+        }
+        self.apply_options(&mut inner, kind);
+        inner
+    }
+
+    fn inner_with<'c, 'd>(&self, prefix: &'c str, suffix: &'d str) -> tempfile::Builder<'c, 'd> {
+        let mut inner = tempfile::Builder::new();
+        inner.prefix(prefix).suffix(suffix);
+        self.apply_options(&mut inner, EntryKind::File);
+        inner
+    }
+
+    fn apply_options(&self, inner: &mut tempfile::Builder<'_, '_>, kind: EntryKind) {
+        if let Some(rand_bytes) = self.rand_bytes {
+            inner.rand_bytes(rand_bytes);
+        }
+        if let Some(permissions) = self.creation_permissions(kind) {
+            inner.permissions(permissions);
+        }
+        inner
+            .append(self.append)
+            .disable_cleanup(self.disable_cleanup);
+    }
+}
+
+/// The kind of entry passed to [`Builder::after_create`].
+#[derive(Clone, Copy)]
+#[cfg_attr(not(unix), allow(dead_code))]
+enum Created<'f> {
+    /// A temporary file, along with its open handle.
+    File(&'f fs::File),
+    /// A temporary directory.
+    Dir,
+    /// An entry created with [`Builder::make`].
+    Made,
+}
+
+/// Opens the directory at `path`, checking that it wasn't replaced with a
+/// symbolic link or another directory while being opened.
+#[cfg(unix)]
+fn open_dir(path: &Utf8Path) -> io::Result<fs::File> {
+    use std::os::unix::fs::MetadataExt;
+
+    let dir = fs::File::open(path)?;
+    let opened = dir.metadata()?;
+    let current = fs::symlink_metadata(path)?;
+    if current.is_dir() && current.dev() == opened.dev() && current.ino() == opened.ino() {
+        Ok(dir)
+    } else {
+        Err(io::Error::other(
+            "temporary directory was replaced before its mode could be set",
+        ))
     }
 }
@@ -0,0 +1,113 @@
+// Copyright (c) The camino-tempfile Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::{NamedUtf8TempFile, Utf8TempDir, Utf8TempPath};
+use std::{convert::TryFrom, error, fmt, path::Path};
+use tempfile::{NamedTempFile, TempDir, TempPath};
+
+/// The error returned when converting one of [`tempfile`]'s types into the
+/// corresponding type in this crate fails, because its path is not valid UTF-8.
+///
+/// The original value can be recovered with [`FromTempfileError::into_inner`].
+pub struct FromTempfileError<T> {
+    inner: T,
+}
+
+impl<T: AsRef<Path>> FromTempfileError<T> {
+    /// Returns the non-UTF-8 path that caused the conversion to fail.
+    pub fn path(&self) -> &Path {
+        self.inner.as_ref()
+    }
+}
+
+impl<T> FromTempfileError<T> {
+    /// Returns the original value that could not be converted.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T: AsRef<Path>> fmt::Debug for FromTempfileError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FromTempfileError")
+            .field("path", &self.path())
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T: AsRef<Path>> fmt::Display for FromTempfileError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "temporary path is not valid UTF-8: {}",
+            self.path().display()
+        )
+    }
+}
+
+impl<T: AsRef<Path>> error::Error for FromTempfileError<T> {}
+
+fn check_utf8<T: AsRef<Path>>(inner: T) -> Result<T, FromTempfileError<T>> {
+    if inner.as_ref().to_str().is_some() {
+        Ok(inner)
+    } else {
+        Err(FromTempfileError { inner })
+    }
+}
+
+/// Converts a [`TempDir`] into a [`Utf8TempDir`], if its path is valid UTF-8.
+///
+/// `TempDir` doesn't expose whether cleanup has been disabled, so it is left
+/// as it is: when the resulting `Utf8TempDir` is dropped, the directory is
+/// removed only if the `TempDir` would have removed it. Until
+/// [`Utf8TempDir::disable_cleanup`] is called, errors during that cleanup are
+/// not reported through the `tracing` feature.
+impl TryFrom<TempDir> for Utf8TempDir {
+    type Error = FromTempfileError<TempDir>;
+
+    fn try_from(inner: TempDir) -> Result<Self, Self::Error> {
+        let inner = check_utf8(inner)?;
+        let mut dir = Self::from_temp_dir(inner).expect("path was checked to be UTF-8");
+        // Leave cleanup on drop to the `TempDir`, which knows whether it's
+        // been disabled.
+        dir.set_cleanup_options(true, false);
+        Ok(dir)
+    }
+}
+
+/// Converts a [`NamedTempFile`] into a [`NamedUtf8TempFile`], if its path is
+/// valid UTF-8.
+///
+/// `NamedTempFile` doesn't expose whether cleanup has been disabled, so it is
+/// left as it is: when the resulting `NamedUtf8TempFile` is dropped, the file
+/// is removed only if the `NamedTempFile` would have removed it. Until
+/// [`NamedUtf8TempFile::disable_cleanup`] is called, errors during that cleanup
+/// are not reported through the `tracing` feature.
+impl<F> TryFrom<NamedTempFile<F>> for NamedUtf8TempFile<F> {
+    type Error = FromTempfileError<NamedTempFile<F>>;
+
+    fn try_from(inner: NamedTempFile<F>) -> Result<Self, Self::Error> {
+        let inner = check_utf8(inner)?;
+        // Leave cleanup on drop to the `NamedTempFile`, which knows whether
+        // it's been disabled.
+        Ok(Self::from_temp_file(inner, true).expect("path was checked to be UTF-8"))
+    }
+}
+
+/// Converts a [`TempPath`] into a [`Utf8TempPath`], if it is valid UTF-8.
+///
+/// `TempPath` doesn't expose whether cleanup has been disabled, so it is left
+/// as it is: when the resulting `Utf8TempPath` is dropped, the file is removed
+/// only if the `TempPath` would have removed it. Until
+/// [`Utf8TempPath::disable_cleanup`] is called, errors during that cleanup are
+/// not reported through the `tracing` feature.
+impl TryFrom<TempPath> for Utf8TempPath {
+    type Error = FromTempfileError<TempPath>;
+
+    fn try_from(inner: TempPath) -> Result<Self, Self::Error> {
+        let inner = check_utf8(inner)?;
+        // Leave cleanup on drop to the `TempPath`, which knows whether it's
+        // been disabled.
+        Ok(Self::from_temp_path(inner, true).expect("path was checked to be UTF-8"))
+    }
+}
@@ -0,0 +1,198 @@
+// Copyright (c) The camino-tempfile Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::errors::IoResultExt;
+use camino::Utf8Path;
+use std::{
+    fs::{self, File},
+    io,
+};
+
+/// The strategy used to copy a file, as reported by
+/// [`NamedUtf8TempFile::copy_of`](crate::NamedUtf8TempFile::copy_of) and
+/// [`Utf8TempDir::clone_tree_from`](crate::Utf8TempDir::clone_tree_from).
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum CopyStrategy {
+    /// The file was cloned with copy-on-write (a "reflink"), using the
+    /// `FICLONE` ioctl. This is nearly free, and the copy is fully independent
+    /// of the original.
+    ///
+    /// Only supported on Linux, on filesystems such as Btrfs and XFS, when the
+    /// source and destination are on the same filesystem.
+    Reflink,
+
+    /// The file was hard-linked to the original. Modifying the contents of the
+    /// copy modifies the original as well.
+    ///
+    /// Only used by
+    /// [`Utf8TempDir::link_tree_from`](crate::Utf8TempDir::link_tree_from),
+    /// when the source and destination are on the same filesystem.
+    Hardlink,
+
+    /// The file's contents were copied.
+    ///
+    /// On Linux, this uses `copy_file_range(2)` where possible, which some
+    /// filesystems implement with copy-on-write as well.
+    Copy,
+}
+
+/// A summary of the strategies used to copy files, returned by
+/// [`Utf8TempDir::clone_tree_from`](crate::Utf8TempDir::clone_tree_from) and
+/// [`Utf8TempDir::link_tree_from`](crate::Utf8TempDir::link_tree_from).
+///
+/// Only regular files are counted: directories are always created anew, and
+/// symbolic links are always recreated.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct CloneTreeReport {
+    reflinked: usize,
+    hardlinked: usize,
+    copied: usize,
+}
+
+impl CloneTreeReport {
+    /// Returns the number of files copied with `strategy`.
+    pub fn count(&self, strategy: CopyStrategy) -> usize {
+        match strategy {
+            CopyStrategy::Reflink => self.reflinked,
+            CopyStrategy::Hardlink => self.hardlinked,
+            CopyStrategy::Copy => self.copied,
+        }
+    }
+
+    /// Returns the total number of files copied.
+    pub fn total(&self) -> usize {
+        self.reflinked + self.hardlinked + self.copied
+    }
+
+    fn record(&mut self, strategy: CopyStrategy) {
+        match strategy {
+            CopyStrategy::Reflink => self.reflinked += 1,
+            CopyStrategy::Hardlink => self.hardlinked += 1,
+            CopyStrategy::Copy => self.copied += 1,
+        }
+    }
+}
+
+/// Copies the contents of `src` into the empty file `dest`, preferring a
+/// reflink.
+pub(crate) fn copy_contents(src: &File, dest: &mut File) -> io::Result<CopyStrategy> {
+    if try_reflink(src, dest) {
+        return Ok(CopyStrategy::Reflink);
+    }
+    // `io::copy` uses `copy_file_range` on Linux for file-to-file copies.
+    io::copy(&mut &*src, dest)?;
+    Ok(CopyStrategy::Copy)
+}
+
+/// Recursively copies the contents of the directory `src` into the empty
+/// directory `dest`.
+pub(crate) fn clone_tree(
+    src: &Utf8Path,
+    dest: &Utf8Path,
+    allow_hardlinks: bool,
+) -> io::Result<CloneTreeReport> {
+    let mut report = CloneTreeReport::default();
+    clone_tree_impl(src, dest, allow_hardlinks, &mut report)?;
+    Ok(report)
+}
+
+fn clone_tree_impl(
+    src: &Utf8Path,
+    dest: &Utf8Path,
+    allow_hardlinks: bool,
+    report: &mut CloneTreeReport,
+) -> io::Result<()> {
+    for entry in src.read_dir_utf8().with_err_path(|| src)? {
+        let entry = entry.with_err_path(|| src)?;
+        let src_path = entry.path();
+        let dest_path = dest.join(entry.file_name());
+        let file_type = entry.file_type().with_err_path(|| src_path)?;
+
+        if file_type.is_dir() {
+            fs::create_dir(&dest_path).with_err_path(|| &dest_path)?;
+            clone_tree_impl(src_path, &dest_path, allow_hardlinks, report)?;
+        } else if file_type.is_symlink() {
+            copy_symlink(src_path, &dest_path)?;
+        } else {
+            report.record(clone_file(src_path, &dest_path, allow_hardlinks)?);
+        }
+    }
+    Ok(())
+}
+
+fn clone_file(src: &Utf8Path, dest: &Utf8Path, allow_hardlinks: bool) -> io::Result<CopyStrategy> {
+    let src_file = File::open(src).with_err_path(|| src)?;
+    let dest_file = File::options()
+        .write(true)
+        .create_new(true)
+        .open(dest)
+        .with_err_path(|| dest)?;
+    if try_reflink(&src_file, &dest_file) {
+        let permissions = src_file.metadata().with_err_path(|| src)?.permissions();
+        dest_file
+            .set_permissions(permissions)
+            .with_err_path(|| dest)?;
+        return Ok(CopyStrategy::Reflink);
+    }
+    drop(dest_file);
+
+    if allow_hardlinks {
+        fs::remove_file(dest).with_err_path(|| dest)?;
+        if fs::hard_link(src, dest).is_ok() {
+            return Ok(CopyStrategy::Hardlink);
+        }
+    }
+
+    // `fs::copy` uses `copy_file_range` on Linux, and copies permissions.
+    fs::copy(src, dest).with_err_path(|| dest)?;
+    Ok(CopyStrategy::Copy)
+}
+
+fn copy_symlink(src: &Utf8Path, dest: &Utf8Path) -> io::Result<()> {
+    let target = fs::read_link(src).with_err_path(|| src)?;
+
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(&target, dest).with_err_path(|| dest)?;
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::{symlink_dir, symlink_file};
+
+        if fs::metadata(src).map(|m| m.is_dir()).unwrap_or(false) {
+            symlink_dir(&target, dest).with_err_path(|| dest)?;
+        } else {
+            symlink_file(&target, dest).with_err_path(|| dest)?;
+        }
+    }
+
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = target;
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "symbolic links are not supported on this platform",
+        ))
+        .with_err_path(|| dest);
+    }
+
+    Ok(())
+}
+
+/// Attempts to clone `src` into `dest` with copy-on-write, returning true on
+/// success.
+#[cfg(all(
+    target_os = "linux",
+    not(any(target_arch = "sparc", target_arch = "sparc64"))
+))]
+fn try_reflink(src: &File, dest: &File) -> bool {
+    rustix::fs::ioctl_ficlone(dest, src).is_ok()
+}
+
+#[cfg(not(all(
+    target_os = "linux",
+    not(any(target_arch = "sparc", target_arch = "sparc64"))
+)))]
+fn try_reflink(_src: &File, _dest: &File) -> bool {
+    false
+}
@@ -1,13 +1,18 @@
 // Copyright (c) The camino-tempfile Contributors
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use crate::Builder;
-use camino::{Utf8Path, Utf8PathBuf};
-use std::{
-    convert::{TryFrom, TryInto},
-    fmt, io,
-    path::Path,
+#[cfg(unix)]
+use crate::process_shared::ProcessLock;
+use crate::{
+    Builder, CloneTreeReport, SharedUtf8TempDir, Utf8TempOperation,
+    background::remove_dir_all_in_background,
+    copy::clone_tree,
+    errors::{IoResultExt, non_utf8_error},
+    helpers,
+    trace::{self, EntryKind},
 };
+use camino::{Utf8Path, Utf8PathBuf};
+use std::{convert::TryInto, fmt, io, path::Path};
 use tempfile::TempDir;
 
 /// Create a new temporary directory.
@@ -135,6 +140,10 @@ pub fn tempdir_in<P: AsRef<Utf8Path>>(dir: P) -> io::Result<Utf8TempDir> {
 /// [`std::process::exit()`], by segfaulting, or by receiving a signal like `SIGINT`, then the
 /// temporary directory will not be deleted.
 ///
+/// Deleting a large directory can take a while. To avoid blocking the current thread, use
+/// [`Utf8TempDir::close_in_background`], or set [`Builder::background_cleanup`] to make that the
+/// behavior on drop.
+///
 /// # Examples
 ///
 /// Create a temporary directory with a generated name:
@@ -166,15 +175,59 @@ pub fn tempdir_in<P: AsRef<Utf8Path>>(dir: P) -> io::Result<Utf8TempDir> {
 /// # }
 /// ```
 pub struct Utf8TempDir {
-    inner: TempDir,
+    // Invariant: `inner` is only `None` after `into_inner` has moved it out.
+    inner: Option<TempDir>,
+    // `TempDir` doesn't expose whether cleanup is disabled, so track it here as well. This is
+    // also set for values converted from a `TempDir`, leaving cleanup to its destructor.
+    disable_cleanup: bool,
+    background_cleanup: bool,
+    // Set if the directory is shared across processes (see `Builder::process_shared`).
+    #[cfg(unix)]
+    process_lock: Option<ProcessLock>,
 }
 
 impl Utf8TempDir {
     pub(crate) fn from_temp_dir(inner: TempDir) -> io::Result<Self> {
         let path = inner.path();
-        // This produces a better error message.
-        Utf8PathBuf::try_from(path.to_path_buf()).map_err(|error| error.into_io_error())?;
-        Ok(Self { inner })
+        if path.to_str().is_none() {
+            return Err(non_utf8_error(path));
+        }
+        Ok(Self {
+            inner: Some(inner),
+            disable_cleanup: false,
+            background_cleanup: false,
+            #[cfg(unix)]
+            process_lock: None,
+        })
+    }
+
+    fn inner(&self) -> &TempDir {
+        self.inner
+            .as_ref()
+            .expect("invariant: inner is only taken by into_inner")
+    }
+
+    pub(crate) fn set_cleanup_options(&mut self, disable_cleanup: bool, background_cleanup: bool) {
+        self.disable_cleanup = disable_cleanup;
+        self.background_cleanup = background_cleanup;
+    }
+
+    #[cfg(unix)]
+    pub(crate) fn set_process_lock(&mut self, lock: ProcessLock) {
+        self.process_lock = Some(lock);
+    }
+
+    /// Releases this process's lock on a directory shared across processes, if any.
+    ///
+    /// Returns false if other processes are still using the directory, in which case it must not
+    /// be removed. Otherwise, an exclusive lock is held until `self` is dropped.
+    fn release_process_lock(&mut self) -> bool {
+        #[cfg(unix)]
+        if let Some(lock) = self.process_lock.take() {
+            self.process_lock = lock.release();
+            return self.process_lock.is_some();
+        }
+        true
     }
 
     /// Attempts to make a temporary directory inside of `env::temp_dir()`.
@@ -363,6 +416,77 @@ impl Utf8TempDir {
         Builder::new().suffix(&suffix).tempdir_in(dir)
     }
 
+    /// Create a new temporary directory in [`std::env::temp_dir()`] containing a copy of the
+    /// contents of the directory at `src`, returning the directory along with a report of the
+    /// strategies used to copy files.
+    ///
+    /// Files are cloned with copy-on-write where the filesystem supports it (see
+    /// [`CopyStrategy::Reflink`](crate::CopyStrategy::Reflink)), and copied otherwise. Either
+    /// way, the copies can be modified without affecting `src`. Directories are created anew and
+    /// symbolic links are recreated with the same targets.
+    ///
+    /// For read-only use, [`Utf8TempDir::link_tree_from`] can be cheaper.
+    ///
+    /// # Errors
+    ///
+    /// If `src` can not be read, or if any entry can not be copied, `Err` is returned. The
+    /// partially-copied temporary directory is deleted in that case.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use camino_tempfile::{CopyStrategy, Utf8TempDir};
+    /// use std::fs;
+    ///
+    /// # fn main() -> std::io::Result<()> {
+    /// # let fixtures = Utf8TempDir::new()?;
+    /// # fs::write(fixtures.path().join("data.txt"), "fixture contents")?;
+    /// # let fixtures_path = fixtures.path();
+    /// let (dir, report) = Utf8TempDir::clone_tree_from(fixtures_path)?;
+    /// assert_eq!(report.count(CopyStrategy::Hardlink), 0);
+    ///
+    /// fs::write(dir.path().join("data.txt"), "modified")?;
+    /// assert_eq!(fs::read_to_string(fixtures_path.join("data.txt"))?, "fixture contents");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn clone_tree_from<P: AsRef<Utf8Path>>(
+        src: P,
+    ) -> io::Result<(Utf8TempDir, CloneTreeReport)> {
+        Self::clone_tree_impl(src.as_ref(), false)
+    }
+
+    /// Create a new temporary directory in [`std::env::temp_dir()`] containing a copy of the
+    /// contents of the directory at `src`, hard-linking files where possible.
+    ///
+    /// This is meant for read-only use. Files are cloned with copy-on-write where the filesystem
+    /// supports it, then hard-linked if `src` is on the same filesystem as the temporary
+    /// directory, and copied otherwise. **Modifying a hard-linked file modifies the original as
+    /// well**: check [`CloneTreeReport::count`] with
+    /// [`CopyStrategy::Hardlink`](crate::CopyStrategy::Hardlink) to see whether any files were
+    /// hard-linked. Replacing or deleting files in the returned directory is always safe.
+    ///
+    /// Otherwise, this is the same as [`Utf8TempDir::clone_tree_from`].
+    ///
+    /// # Errors
+    ///
+    /// If `src` can not be read, or if any entry can not be copied, `Err` is returned. The
+    /// partially-copied temporary directory is deleted in that case.
+    pub fn link_tree_from<P: AsRef<Utf8Path>>(
+        src: P,
+    ) -> io::Result<(Utf8TempDir, CloneTreeReport)> {
+        Self::clone_tree_impl(src.as_ref(), true)
+    }
+
+    fn clone_tree_impl(
+        src: &Utf8Path,
+        allow_hardlinks: bool,
+    ) -> io::Result<(Utf8TempDir, CloneTreeReport)> {
+        let dir = Utf8TempDir::new()?;
+        let report = clone_tree(src, dir.path(), allow_hardlinks)?;
+        Ok((dir, report))
+    }
+
     /// Accesses the [`Utf8Path`] to the temporary directory.
     ///
     /// # Examples
@@ -426,11 +550,10 @@ impl Utf8TempDir {
     /// # }
     /// ```
     #[must_use]
-    pub fn keep(self) -> Utf8PathBuf {
-        self.inner
-            .keep()
-            .try_into()
-            .expect("invariant: path is valid UTF-8")
+    pub fn keep(mut self) -> Utf8PathBuf {
+        self.disable_cleanup(true);
+        trace::keep(EntryKind::Dir, self.path(), None);
+        self.path().to_owned()
     }
 
     /// Disable cleanup of the temporary directory. If `disable_cleanup` is
@@ -442,7 +565,10 @@ impl Utf8TempDir {
     /// want to simply turn a temporary directory into a non-temporary
     /// directory, prefer [`Utf8TempDir::keep`].
     pub fn disable_cleanup(&mut self, disable_cleanup: bool) {
-        self.inner.disable_cleanup(disable_cleanup);
+        if let Some(inner) = &mut self.inner {
+            inner.disable_cleanup(disable_cleanup);
+        }
+        self.disable_cleanup = disable_cleanup;
     }
 
     /// Closes and removes the temporary directory, returning a `Result`.
@@ -450,6 +576,10 @@ impl Utf8TempDir {
     /// Although `Utf8TempDir` removes the directory on drop, in the destructor any errors are
     /// ignored. To detect errors cleaning up the temporary directory, call `close` instead.
     ///
+    /// If the directory is shared with other processes (see [`Builder::process_shared`]) that are
+    /// still using it, this releases this process's lock and returns `Ok(())` without removing
+    /// the directory.
+    ///
     /// # Errors
     ///
     /// This function may return a variety of [`std::io::Error`]s that result from deleting the
@@ -480,8 +610,105 @@ impl Utf8TempDir {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn close(self) -> io::Result<()> {
-        self.inner.close()
+    pub fn close(mut self) -> io::Result<()> {
+        // Disable cleanup so that the destructor doesn't try to remove the directory again.
+        self.disable_cleanup(true);
+        if !self.release_process_lock() {
+            return Ok(());
+        }
+        let result = helpers::remove_dir_all(self.path())
+            .with_err_op(Utf8TempOperation::Cleanup, || self.path());
+        trace::close(EntryKind::Dir, self.path(), &result);
+        result
+    }
+
+    /// Closes the temporary directory, removing it on a background thread.
+    ///
+    /// The directory is first renamed to a hidden name in the same parent directory, so that it
+    /// disappears from its original location immediately. The renamed directory is then deleted
+    /// on a background thread, without blocking the current thread. If the directory cannot be
+    /// renamed, it is deleted from its original location instead.
+    ///
+    /// As with the destructor, any errors while deleting the directory are ignored. Use
+    /// [`Utf8TempDir::close`] to detect errors instead.
+    ///
+    /// Background deletions are not waited on when the process exits; call
+    /// [`wait_for_background_cleanup`](crate::wait_for_background_cleanup) before exiting to
+    /// ensure that they've finished.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use camino_tempfile::{Utf8TempDir, wait_for_background_cleanup};
+    /// use std::fs;
+    ///
+    /// # use std::io;
+    /// # fn run() -> Result<(), io::Error> {
+    /// let tmp_dir = Utf8TempDir::new()?;
+    /// let tmp_path = tmp_dir.path().to_owned();
+    /// fs::create_dir_all(tmp_path.join("a/very/deep/tree"))?;
+    ///
+    /// tmp_dir.close_in_background();
+    /// assert!(!tmp_path.exists());
+    ///
+    /// // Later, before exiting:
+    /// wait_for_background_cleanup();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn close_in_background(mut self) {
+        self.disable_cleanup(true);
+        if !self.release_process_lock() {
+            return;
+        }
+        remove_dir_all_in_background(self.path());
+    }
+
+    /// Converts this `Utf8TempDir` into a [`SharedUtf8TempDir`], which can be cloned and shared
+    /// across threads.
+    ///
+    /// The directory is deleted when the last handle to it is dropped.
+    pub fn into_shared(self) -> SharedUtf8TempDir {
+        SharedUtf8TempDir::from(self)
+    }
+
+    /// Converts this `Utf8TempDir` into the underlying [`TempDir`].
+    ///
+    /// Whether cleanup is disabled carries over to the `TempDir`, but
+    /// [`Builder::background_cleanup`] does not: the `TempDir` will always be
+    /// removed on the current thread.
+    ///
+    /// If the directory is shared with other processes (see [`Builder::process_shared`]), this
+    /// process's lock is released, and the `TempDir` removes the directory when dropped even if
+    /// other processes are still using it.
+    ///
+    /// To go the other way, use `Utf8TempDir::try_from`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use camino_tempfile::Utf8TempDir;
+    /// use std::convert::TryFrom;
+    ///
+    /// # fn main() -> std::io::Result<()> {
+    /// let tmp_dir = Utf8TempDir::new()?;
+    /// let path = tmp_dir.path().to_owned();
+    ///
+    /// let inner: tempfile::TempDir = tmp_dir.into_inner();
+    /// assert_eq!(inner.path(), path);
+    ///
+    /// let tmp_dir = Utf8TempDir::try_from(inner).expect("path is valid UTF-8");
+    /// assert_eq!(tmp_dir.path(), path);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_inner(mut self) -> TempDir {
+        #[cfg(unix)]
+        drop(self.process_lock.take());
+        // The destructor skips cleanup once `inner` has been taken.
+        self.inner
+            .take()
+            .expect("invariant: inner is only taken by into_inner")
     }
 }
 
@@ -495,7 +722,7 @@ impl AsRef<Utf8Path> for Utf8TempDir {
 
 impl AsRef<Path> for Utf8TempDir {
     fn as_ref(&self) -> &Path {
-        self.inner.path()
+        self.inner().path()
     }
 }
 
@@ -507,4 +734,23 @@ impl fmt::Debug for Utf8TempDir {
     }
 }
 
-// The Drop impl is implicit since `Utf8TempDir` wraps a `TempDir`.
+impl Drop for Utf8TempDir {
+    fn drop(&mut self) {
+        if self.disable_cleanup || self.inner.is_none() {
+            return;
+        }
+        // Remove the directory here rather than in the `TempDir` destructor, so that errors can
+        // be reported.
+        self.disable_cleanup(true);
+        if !self.release_process_lock() {
+            return;
+        }
+        if self.background_cleanup {
+            remove_dir_all_in_background(self.path());
+        } else {
+            let result = helpers::remove_dir_all(self.path())
+                .with_err_op(Utf8TempOperation::Cleanup, || self.path());
+            trace::drop_cleanup(EntryKind::Dir, self.path(), &result);
+        }
+    }
+}
@@ -1,32 +1,213 @@
 // Copyright (c) The camino-tempfile Contributors
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use camino::Utf8PathBuf;
-use std::{error, fmt, io};
+use camino::{Utf8Path, Utf8PathBuf};
+use std::{
+    error, fmt, io,
+    path::{Path, PathBuf},
+};
 
+/// An error that occurred while operating on a temporary file or directory.
+///
+/// For compatibility with [`std::io`], the methods in this crate return
+/// [`io::Error`]s. Errors that originate in this crate wrap a `Utf8TempError`,
+/// which can be retrieved with [`Utf8TempError::from_io_error`] to find out
+/// which operation failed, the path involved, and why.
+///
+/// Errors returned by closures passed in to this crate, such as the one passed
+/// to [`Builder::make`](crate::Builder::make), are wrapped as well.
+///
+/// # Examples
+///
+/// ```
+/// use camino_tempfile::{Builder, Utf8TempError, Utf8TempOperation};
+///
+/// let error = Builder::new()
+///     .tempfile_in("/this/directory/does/not/exist")
+///     .unwrap_err();
+/// let error = Utf8TempError::from_io_error(&error).expect("error is a Utf8TempError");
+///
+/// assert_eq!(error.operation(), Utf8TempOperation::CreateFile);
+/// assert_eq!(error.path().unwrap(), "/this/directory/does/not/exist");
+/// assert!(!error.is_non_utf8());
+/// assert!(error.io_error().is_some());
+/// ```
 #[derive(Debug)]
-struct Utf8PathError {
-    path: Utf8PathBuf,
-    err: io::Error,
+pub struct Utf8TempError {
+    operation: Utf8TempOperation,
+    path: PathBuf,
+    // None if the path is not valid UTF-8.
+    error: Option<io::Error>,
+    // True if `error` already mentions the path it occurred at, as errors from
+    // tempfile do.
+    path_in_message: bool,
 }
 
-impl fmt::Display for Utf8PathError {
+impl Utf8TempError {
+    /// Returns the `Utf8TempError` wrapped by `error`, if any.
+    pub fn from_io_error(error: &io::Error) -> Option<&Self> {
+        error.get_ref()?.downcast_ref()
+    }
+
+    /// Returns the operation that failed.
+    #[inline]
+    pub fn operation(&self) -> Utf8TempOperation {
+        self.operation
+    }
+
+    /// Returns the path involved in the failed operation, if it is valid UTF-8.
+    ///
+    /// Depending on the operation, this is:
+    ///
+    /// - [`CreateFile`](Utf8TempOperation::CreateFile),
+    ///   [`CreateDir`](Utf8TempOperation::CreateDir): the directory the
+    ///   temporary entry was being created in.
+    /// - [`Persist`](Utf8TempOperation::Persist): the path the temporary file
+    ///   was being persisted to. When keeping a temporary file, this is the
+    ///   path to the file itself, since it's kept in place.
+    /// - Otherwise: the path to the temporary entry, or for
+    ///   [`ValidateUtf8`](Utf8TempOperation::ValidateUtf8), the path being
+    ///   validated.
+    ///
+    /// Returns `None` if the path is not valid UTF-8; use
+    /// [`Utf8TempError::std_path`] to get it in that case.
+    pub fn path(&self) -> Option<&Utf8Path> {
+        Utf8Path::from_path(&self.path)
+    }
+
+    /// Returns the path involved in the failed operation, even if it is not
+    /// valid UTF-8.
+    #[inline]
+    pub fn std_path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns true if the operation failed because a path was not valid UTF-8.
+    #[inline]
+    pub fn is_non_utf8(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Returns the underlying I/O error, or `None` if the operation failed
+    /// because a path was not valid UTF-8.
+    ///
+    /// To get the raw OS error code, use [`io::Error::raw_os_error`] on the
+    /// returned error.
+    #[inline]
+    pub fn io_error(&self) -> Option<&io::Error> {
+        self.error.as_ref()
+    }
+
+    fn kind(&self) -> io::ErrorKind {
+        match &self.error {
+            Some(error) => error.kind(),
+            None => io::ErrorKind::InvalidData,
+        }
+    }
+}
+
+impl fmt::Display for Utf8TempError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{} at path {}", self.err, self.path)
+        let path = self.path.display();
+        let Some(err) = &self.error else {
+            return write!(f, "path is not valid UTF-8: {path}");
+        };
+
+        // Errors from tempfile already mention the path they occurred at (for
+        // example, the temporary file inside the directory it was being
+        // created in), so don't repeat it for those.
+        let message = err.to_string();
+        match (self.operation, self.path_in_message) {
+            (Utf8TempOperation::CreateFile, false) => {
+                write!(f, "{message} (creating temporary file in {path})")
+            }
+            (Utf8TempOperation::CreateFile, true) => {
+                write!(f, "{message} (creating temporary file)")
+            }
+            (Utf8TempOperation::CreateDir, false) => {
+                write!(f, "{message} (creating temporary directory in {path})")
+            }
+            (Utf8TempOperation::CreateDir, true) => {
+                write!(f, "{message} (creating temporary directory)")
+            }
+            (Utf8TempOperation::Persist, false) => {
+                write!(f, "{message} (persisting temporary file to {path})")
+            }
+            (Utf8TempOperation::Persist, true) => {
+                write!(f, "{message} (persisting temporary file)")
+            }
+            (_, false) => write!(f, "{message} at path {path}"),
+            (_, true) => f.write_str(&message),
+        }
     }
 }
 
-impl error::Error for Utf8PathError {
+impl error::Error for Utf8TempError {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
-        self.err.source()
+        // The error's message is already included in the Display impl.
+        self.error.as_ref()?.source()
     }
 }
 
+/// The operation that failed, as reported by [`Utf8TempError::operation`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+#[non_exhaustive]
+pub enum Utf8TempOperation {
+    /// Creating a temporary file, or another kind of entry created with
+    /// [`Builder::make`](crate::Builder::make).
+    CreateFile,
+
+    /// Creating a temporary directory.
+    CreateDir,
+
+    /// Persisting or keeping a temporary file.
+    Persist,
+
+    /// Removing a temporary file or directory.
+    Cleanup,
+
+    /// Checking that a path is valid UTF-8, for example the path returned by
+    /// [`std::env::temp_dir()`].
+    ValidateUtf8,
+
+    /// Reading from, writing to, or otherwise operating on an existing
+    /// temporary file or directory.
+    Io,
+}
+
+/// Returns an error indicating that `path` is not valid UTF-8.
+pub(crate) fn non_utf8_error(path: impl Into<PathBuf>) -> io::Error {
+    let error = Utf8TempError {
+        operation: Utf8TempOperation::ValidateUtf8,
+        path: path.into(),
+        error: None,
+        path_in_message: false,
+    };
+    io::Error::new(error.kind(), error)
+}
+
 pub(crate) trait IoResultExt<T> {
+    /// Wraps the error with the path it occurred at, as a
+    /// [`Utf8TempOperation::Io`] error.
     fn with_err_path<F, P>(self, path: F) -> Self
     where
         F: FnOnce() -> P,
         P: Into<Utf8PathBuf>;
+
+    /// Wraps the error with the operation and path it occurred at.
+    ///
+    /// Errors that already wrap a `Utf8TempError` are returned unchanged.
+    fn with_err_op<F, P>(self, operation: Utf8TempOperation, path: F) -> Self
+    where
+        F: FnOnce() -> P,
+        P: Into<PathBuf>;
+
+    /// Like [`with_err_op`](Self::with_err_op), for errors returned by
+    /// tempfile, which already mention the path they occurred at.
+    fn with_tempfile_err_op<F, P>(self, operation: Utf8TempOperation, path: F) -> Self
+    where
+        F: FnOnce() -> P,
+        P: Into<PathBuf>;
 }
 
 impl<T> IoResultExt<T> for Result<T, io::Error> {
@@ -35,14 +216,51 @@ impl<T> IoResultExt<T> for Result<T, io::Error> {
         F: FnOnce() -> P,
         P: Into<Utf8PathBuf>,
     {
-        self.map_err(|e| {
-            io::Error::new(
-                e.kind(),
-                Utf8PathError {
-                    path: path().into(),
-                    err: e,
-                },
-            )
-        })
+        self.with_err_op(Utf8TempOperation::Io, || path().into().into_std_path_buf())
+    }
+
+    fn with_err_op<F, P>(self, operation: Utf8TempOperation, path: F) -> Self
+    where
+        F: FnOnce() -> P,
+        P: Into<PathBuf>,
+    {
+        self.map_err(|e| wrap_error(e, operation, path()))
+    }
+
+    fn with_tempfile_err_op<F, P>(self, operation: Utf8TempOperation, path: F) -> Self
+    where
+        F: FnOnce() -> P,
+        P: Into<PathBuf>,
+    {
+        self.map_err(|e| wrap_error_inner(e, operation, path().into(), true))
+    }
+}
+
+/// Wraps `error` with the operation and path it occurred at.
+///
+/// Errors that already wrap a `Utf8TempError` are returned unchanged.
+pub(crate) fn wrap_error(
+    error: io::Error,
+    operation: Utf8TempOperation,
+    path: impl Into<PathBuf>,
+) -> io::Error {
+    wrap_error_inner(error, operation, path.into(), false)
+}
+
+fn wrap_error_inner(
+    error: io::Error,
+    operation: Utf8TempOperation,
+    path: PathBuf,
+    path_in_message: bool,
+) -> io::Error {
+    if Utf8TempError::from_io_error(&error).is_some() {
+        return error;
     }
+    let error = Utf8TempError {
+        operation,
+        path,
+        error: Some(error),
+        path_in_message,
+    };
+    io::Error::new(error.kind(), error)
 }
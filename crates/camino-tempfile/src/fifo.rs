@@ -0,0 +1,214 @@
+// Copyright (c) The camino-tempfile Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::{Utf8PathPersistError, Utf8TempPath, errors::IoResultExt};
+use camino::{Utf8Path, Utf8PathBuf};
+use std::{
+    ffi::CString,
+    fmt,
+    fs::{File, OpenOptions},
+    io,
+    ops::Deref,
+    os::unix::{fs::OpenOptionsExt, io::AsRawFd},
+    path::Path,
+};
+
+/// A named pipe (FIFO), created with [`Builder::fifo`] or [`Builder::fifo_in`].
+///
+/// When dropped, the FIFO is deleted.
+///
+/// # Opening the FIFO
+///
+/// Opening one end of a FIFO normally blocks until the other end is opened,
+/// which can deadlock a single thread that needs both ends. The helpers on
+/// this type avoid that:
+///
+/// - [`Utf8TempFifo::open_read`] returns immediately, even if there's no
+///   writer yet.
+/// - [`Utf8TempFifo::open_pair`] opens both ends at once, without blocking.
+/// - [`Utf8TempFifo::open_write`] blocks until a reader has opened the FIFO,
+///   as usual. Open the read end first (possibly in another process) to avoid
+///   blocking.
+///
+/// The files returned by all of these are in blocking mode.
+///
+/// # Examples
+///
+/// ```
+/// use camino_tempfile::Builder;
+/// use std::io::{Read, Write};
+///
+/// # fn main() -> std::io::Result<()> {
+/// let fifo = Builder::new().prefix("my-fifo").fifo()?;
+///
+/// let (mut reader, mut writer) = fifo.open_pair()?;
+/// writer.write_all(b"Brian was here. Briefly.")?;
+/// drop(writer);
+///
+/// let mut contents = String::new();
+/// reader.read_to_string(&mut contents)?;
+/// assert_eq!(contents, "Brian was here. Briefly.");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// [`Builder::fifo`]: crate::Builder::fifo
+/// [`Builder::fifo_in`]: crate::Builder::fifo_in
+pub struct Utf8TempFifo {
+    path: Utf8TempPath,
+}
+
+impl Utf8TempFifo {
+    pub(crate) fn new(path: Utf8TempPath) -> Self {
+        Self { path }
+    }
+
+    /// Get the FIFO's path.
+    #[inline]
+    #[must_use]
+    pub fn path(&self) -> &Utf8Path {
+        &self.path
+    }
+
+    /// Open the read end of the FIFO.
+    ///
+    /// This returns immediately, even if no writer has opened the FIFO yet.
+    /// Until a writer opens the FIFO, reads from the returned file report
+    /// end-of-file.
+    ///
+    /// # Errors
+    ///
+    /// If the FIFO cannot be opened, `Err` is returned.
+    pub fn open_read(&self) -> io::Result<File> {
+        let file = OpenOptions::new()
+            .read(true)
+            .custom_flags(libc::O_NONBLOCK)
+            .open(self.path())
+            .with_err_path(|| self.path())?;
+        set_blocking(&file).with_err_path(|| self.path())?;
+        Ok(file)
+    }
+
+    /// Open the write end of the FIFO.
+    ///
+    /// This blocks until a reader has opened the FIFO. To open both ends from
+    /// the same thread, use [`Utf8TempFifo::open_pair`], or call
+    /// [`Utf8TempFifo::open_read`] first.
+    ///
+    /// # Errors
+    ///
+    /// If the FIFO cannot be opened, `Err` is returned.
+    pub fn open_write(&self) -> io::Result<File> {
+        OpenOptions::new()
+            .write(true)
+            .open(self.path())
+            .with_err_path(|| self.path())
+    }
+
+    /// Open both ends of the FIFO without blocking, returning `(reader, writer)`.
+    ///
+    /// # Errors
+    ///
+    /// If either end of the FIFO cannot be opened, `Err` is returned.
+    pub fn open_pair(&self) -> io::Result<(File, File)> {
+        // Since the read end is open, opening the write end doesn't block.
+        let reader = self.open_read()?;
+        let writer = self.open_write()?;
+        Ok((reader, writer))
+    }
+
+    /// Close and remove the FIFO.
+    ///
+    /// Use this if you want to detect errors in deleting the FIFO.
+    ///
+    /// # Errors
+    ///
+    /// If the FIFO cannot be deleted, `Err` is returned.
+    pub fn close(self) -> io::Result<()> {
+        self.path.close()
+    }
+
+    /// Keep the FIFO, returning its path.
+    ///
+    /// The FIFO will no longer be deleted when this `Utf8TempFifo` is
+    /// dropped.
+    ///
+    /// # Errors
+    ///
+    /// On some platforms, this can fail; see [`Utf8TempPath::keep`].
+    pub fn keep(self) -> Result<Utf8PathBuf, Utf8PathPersistError> {
+        self.path.keep()
+    }
+
+    /// Disable cleanup of the FIFO. If `disable_cleanup` is `true`, the FIFO
+    /// will not be deleted when this `Utf8TempFifo` is dropped. This method is
+    /// equivalent to calling [`Builder::disable_cleanup`] when creating the
+    /// `Utf8TempFifo`.
+    ///
+    /// [`Builder::disable_cleanup`]: crate::Builder::disable_cleanup
+    pub fn disable_cleanup(&mut self, disable_cleanup: bool) {
+        self.path.disable_cleanup(disable_cleanup);
+    }
+
+    /// Convert the FIFO into a [`Utf8TempPath`], which deletes the FIFO when
+    /// dropped.
+    pub fn into_temp_path(self) -> Utf8TempPath {
+        self.path
+    }
+}
+
+impl fmt::Debug for Utf8TempFifo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Utf8TempFifo")
+            .field("path", &self.path())
+            .finish()
+    }
+}
+
+impl Deref for Utf8TempFifo {
+    type Target = Utf8Path;
+
+    fn deref(&self) -> &Utf8Path {
+        self.path()
+    }
+}
+
+impl AsRef<Utf8Path> for Utf8TempFifo {
+    #[inline]
+    fn as_ref(&self) -> &Utf8Path {
+        self.path()
+    }
+}
+
+impl AsRef<Path> for Utf8TempFifo {
+    #[inline]
+    fn as_ref(&self) -> &Path {
+        self.path().as_std_path()
+    }
+}
+
+/// Creates a FIFO at `path` with the given mode, which is subject to the umask.
+pub(crate) fn mkfifo(path: &Utf8Path, mode: u32) -> io::Result<()> {
+    let c_path = CString::new(path.as_str())
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error))?;
+    // SAFETY: c_path is a valid NUL-terminated string.
+    let ret = unsafe { libc::mkfifo(c_path.as_ptr(), mode as libc::mode_t) };
+    if ret == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+fn set_blocking(file: &File) -> io::Result<()> {
+    let fd = file.as_raw_fd();
+    // SAFETY: fd is a valid, open file descriptor for the duration of these
+    // calls.
+    unsafe {
+        let flags = libc::fcntl(fd, libc::F_GETFL);
+        if flags == -1 || libc::fcntl(fd, libc::F_SETFL, flags & !libc::O_NONBLOCK) == -1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
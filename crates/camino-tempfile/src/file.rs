@@ -1,7 +1,13 @@
 // Copyright (c) The camino-tempfile Contributors
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use crate::{Builder, errors::IoResultExt};
+use crate::{
+    Builder, ContentHasher, CopyStrategy, Digest, HashingWriter, Utf8TempOperation,
+    copy::copy_contents,
+    errors::{IoResultExt, non_utf8_error, wrap_error},
+    hashing::DigestMismatchError,
+    trace::{self, EntryKind},
+};
 use camino::{Utf8Path, Utf8PathBuf};
 use std::{
     convert::{TryFrom, TryInto},
@@ -12,7 +18,7 @@ use std::{
     io,
     io::{Read, Seek, SeekFrom, Write},
     ops::Deref,
-    path::Path,
+    path::{Path, PathBuf},
 };
 use tempfile::{NamedTempFile, TempPath};
 
@@ -145,14 +151,30 @@ impl error::Error for Utf8PathPersistError {
 pub struct Utf8TempPath {
     // Invariant: inner stores a UTF-8 path.
     inner: TempPath,
+    // `TempPath` doesn't expose whether cleanup is disabled, so track it here as well. This is
+    // also set for values converted from a `TempPath`, leaving cleanup to its destructor.
+    disable_cleanup: bool,
 }
 
 impl Utf8TempPath {
-    pub(crate) fn from_temp_path(inner: TempPath) -> io::Result<Self> {
+    pub(crate) fn from_temp_path(inner: TempPath, disable_cleanup: bool) -> io::Result<Self> {
         let path: &Path = inner.as_ref();
-        // This produces a better error message.
-        Utf8PathBuf::try_from(path.to_path_buf()).map_err(|error| error.into_io_error())?;
-        Ok(Self { inner })
+        if path.to_str().is_none() {
+            return Err(non_utf8_error(path));
+        }
+        Ok(Self {
+            inner,
+            disable_cleanup,
+        })
+    }
+
+    /// Takes the inner `TempPath` out, leaving behind an empty path with
+    /// cleanup disabled.
+    fn take_inner(&mut self) -> TempPath {
+        let mut placeholder = TempPath::from_path(PathBuf::new());
+        placeholder.disable_cleanup(true);
+        self.disable_cleanup = true;
+        std::mem::replace(&mut self.inner, placeholder)
     }
 
     /// Close and remove the temporary file.
@@ -188,8 +210,14 @@ impl Utf8TempPath {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn close(self) -> io::Result<()> {
-        self.inner.close()
+    pub fn close(mut self) -> io::Result<()> {
+        let path = self.to_path_buf();
+        let result = self
+            .take_inner()
+            .close()
+            .with_tempfile_err_op(Utf8TempOperation::Cleanup, || &path);
+        trace::close(EntryKind::File, &path, &result);
+        result
     }
 
     /// Persist the temporary file at the target path.
@@ -236,14 +264,27 @@ impl Utf8TempPath {
     /// ```
     ///
     /// [`PathPersistError`]: struct.PathPersistError.html
-    pub fn persist<P: AsRef<Path>>(self, new_path: P) -> Result<(), Utf8PathPersistError> {
-        self.inner.persist(new_path.as_ref()).map_err(|error| {
+    pub fn persist<P: AsRef<Path>>(mut self, new_path: P) -> Result<(), Utf8PathPersistError> {
+        let path = self.to_path_buf();
+        let new_path = new_path.as_ref();
+        let disable_cleanup = self.disable_cleanup;
+        let result = self.take_inner().persist(new_path).map_err(|error| {
             Utf8PathPersistError {
-                error: error.error,
+                error: wrap_error(error.error, Utf8TempOperation::Persist, new_path),
                 // This is OK because the path returned here is self
-                path: Self { inner: error.path },
+                path: Self {
+                    inner: error.path,
+                    disable_cleanup,
+                },
             }
-        })
+        });
+        trace::persist(
+            &path,
+            new_path,
+            false,
+            result.as_ref().err().map(|e| &e.error),
+        );
+        result
     }
 
     /// Persist the temporary file at the target path if and only if no file exists there.
@@ -291,18 +332,32 @@ impl Utf8TempPath {
     ///
     /// [`PathPersistError`]: struct.PathPersistError.html
     pub fn persist_noclobber<P: AsRef<Path>>(
-        self,
+        mut self,
         new_path: P,
     ) -> Result<(), Utf8PathPersistError> {
-        self.inner
-            .persist_noclobber(new_path.as_ref())
+        let path = self.to_path_buf();
+        let new_path = new_path.as_ref();
+        let disable_cleanup = self.disable_cleanup;
+        let result = self
+            .take_inner()
+            .persist_noclobber(new_path)
             .map_err(|error| {
                 Utf8PathPersistError {
-                    error: error.error,
+                    error: wrap_error(error.error, Utf8TempOperation::Persist, new_path),
                     // This is OK because the path returned here is self
-                    path: Self { inner: error.path },
+                    path: Self {
+                        inner: error.path,
+                        disable_cleanup,
+                    },
                 }
-            })
+            });
+        trace::persist(
+            &path,
+            new_path,
+            true,
+            result.as_ref().err().map(|e| &e.error),
+        );
+        result
     }
 
     /// Keep the temporary file from being deleted. This function will turn the temporary file into
@@ -334,14 +389,26 @@ impl Utf8TempPath {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn keep(self) -> Result<Utf8PathBuf, Utf8PathPersistError> {
-        match self.inner.keep() {
-            Ok(path) => Ok(Utf8PathBuf::try_from(path).expect("invariant: path is UTF-8")),
+    pub fn keep(mut self) -> Result<Utf8PathBuf, Utf8PathPersistError> {
+        let disable_cleanup = self.disable_cleanup;
+        match self.take_inner().keep() {
+            Ok(path) => {
+                let path = Utf8PathBuf::try_from(path).expect("invariant: path is UTF-8");
+                trace::keep(EntryKind::File, &path, None);
+                Ok(path)
+            }
             Err(error) => {
+                let path = Self {
+                    inner: error.path,
+                    disable_cleanup,
+                };
+                // The file is kept in place, so its own path is the destination.
+                let error = wrap_error(error.error, Utf8TempOperation::Persist, path.as_str());
+                trace::keep(EntryKind::File, &path, Some(&error));
                 Err(Utf8PathPersistError {
-                    error: error.error,
+                    error,
                     // This is OK because the path returned here is self
-                    path: Self { inner: error.path },
+                    path,
                 })
             }
         }
@@ -358,6 +425,15 @@ impl Utf8TempPath {
     /// file-path, prefer [`Utf8TempPath::keep`].
     pub fn disable_cleanup(&mut self, disable_cleanup: bool) {
         self.inner.disable_cleanup(disable_cleanup);
+        self.disable_cleanup = disable_cleanup;
+    }
+
+    /// Converts this `Utf8TempPath` into the underlying [`TempPath`].
+    ///
+    /// Whether cleanup is disabled carries over to the `TempPath`. To go the other way, use
+    /// `Utf8TempPath::try_from`.
+    pub fn into_inner(mut self) -> TempPath {
+        self.take_inner()
     }
 
     /// Create a new `Utf8TempPath` from an existing path. This can be done even if no file exists
@@ -368,6 +444,22 @@ impl Utf8TempPath {
     pub fn from_path(path: impl Into<Utf8PathBuf>) -> Self {
         Self {
             inner: TempPath::from_path(path.into()),
+            disable_cleanup: false,
+        }
+    }
+}
+
+impl Drop for Utf8TempPath {
+    fn drop(&mut self) {
+        // Remove the file here rather than in the `TempPath` destructor, so that errors can be
+        // reported.
+        if !self.disable_cleanup {
+            let path = self.to_path_buf();
+            let result = self
+                .take_inner()
+                .close()
+                .with_tempfile_err_op(Utf8TempOperation::Cleanup, || &path);
+            trace::drop_cleanup(EntryKind::File, &path, &result);
         }
     }
 }
@@ -497,16 +589,47 @@ impl AsRef<OsStr> for Utf8TempPath {
 ///
 /// Use the [`tempfile()`] function unless you need a named file path.
 pub struct NamedUtf8TempFile<F = File> {
-    // Invariant: inner.path is a valid Utf8TempPath
-    inner: NamedTempFile<F>,
+    // The path is stored as a `Utf8TempPath` rather than within a `NamedTempFile`, so that its
+    // destructor can report cleanup errors. As with `NamedTempFile`, the path is dropped before
+    // the file.
+    path: Utf8TempPath,
+    file: F,
 }
 
 impl<F> NamedUtf8TempFile<F> {
-    pub(crate) fn from_temp_file(inner: NamedTempFile<F>) -> io::Result<Self> {
-        let path = inner.path();
-        // This produces a better error message.
-        Utf8PathBuf::try_from(path.to_owned()).map_err(|error| error.into_io_error())?;
-        Ok(Self { inner })
+    pub(crate) fn from_temp_file(
+        inner: NamedTempFile<F>,
+        disable_cleanup: bool,
+    ) -> io::Result<Self> {
+        let (file, path) = inner.into_parts();
+        let path = Utf8TempPath::from_temp_path(path, disable_cleanup)?;
+        Ok(Self { path, file })
+    }
+
+    fn into_temp_file(self) -> (NamedTempFile<F>, bool) {
+        let Self { mut path, file } = self;
+        let disable_cleanup = path.disable_cleanup;
+        let inner = NamedTempFile::from_parts(file, path.take_inner());
+        (inner, disable_cleanup)
+    }
+
+    fn persist_error(
+        error: tempfile::PersistError<F>,
+        new_path: &Path,
+        disable_cleanup: bool,
+    ) -> Utf8PersistError<F> {
+        let (file, path) = error.file.into_parts();
+        Utf8PersistError {
+            // This is valid because self is exactly error.file.
+            file: NamedUtf8TempFile {
+                path: Utf8TempPath {
+                    inner: path,
+                    disable_cleanup,
+                },
+                file,
+            },
+            error: wrap_error(error.error, Utf8TempOperation::Persist, new_path),
+        }
     }
 }
 
@@ -645,6 +768,86 @@ impl NamedUtf8TempFile<File> {
     ) -> io::Result<NamedUtf8TempFile> {
         Builder::new().suffix(&suffix).tempfile_in(dir)
     }
+
+    /// Create a new named temporary file in the same directory as `target`,
+    /// so that it can later be persisted onto `target` without crossing
+    /// filesystems.
+    ///
+    /// This is equivalent to:
+    ///
+    /// ```ignore
+    /// Builder::new().tempfile_sibling_of(target)
+    /// ```
+    ///
+    /// See [`Builder::tempfile_sibling_of`] for details on how the directory and
+    /// file name are chosen, and [`NamedUtf8TempFile::new()`] for other
+    /// details.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use std::io::{self, Write};
+    /// use camino_tempfile::NamedUtf8TempFile;
+    ///
+    /// # fn main() {
+    /// #     if let Err(_) = run() {
+    /// #         ::std::process::exit(1);
+    /// #     }
+    /// # }
+    /// # fn run() -> Result<(), io::Error> {
+    /// let mut file = NamedUtf8TempFile::new_sibling_of("saved_file.txt")?;
+    /// writeln!(file, "Brian was here. Briefly.")?;
+    ///
+    /// file.persist("saved_file.txt")?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn new_sibling_of<P: AsRef<Utf8Path>>(target: P) -> io::Result<NamedUtf8TempFile> {
+        Builder::new().tempfile_sibling_of(target)
+    }
+
+    /// Create a new named temporary file in [`std::env::temp_dir()`] containing a copy of the
+    /// file at `src`, returning the file along with the strategy used to copy it.
+    ///
+    /// Where the filesystem supports it, the file is cloned with copy-on-write (see
+    /// [`CopyStrategy::Reflink`]), which is nearly free even for large files. Otherwise, its
+    /// contents are copied. Either way, the copy can be modified without affecting `src`.
+    ///
+    /// The returned file is positioned at the start. Its permissions are those of a new
+    /// temporary file, not those of `src`.
+    ///
+    /// # Errors
+    ///
+    /// If `src` can not be opened, or the temporary file can not be created or written to, `Err`
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use camino_tempfile::{CopyStrategy, NamedUtf8TempFile};
+    /// use std::{fs, io::Write};
+    ///
+    /// # fn main() -> std::io::Result<()> {
+    /// # let mut fixture = NamedUtf8TempFile::new()?;
+    /// # write!(fixture, "fixture contents")?;
+    /// # let fixture_path = fixture.path();
+    /// let (mut file, strategy) = NamedUtf8TempFile::copy_of(fixture_path)?;
+    /// assert!(matches!(strategy, CopyStrategy::Reflink | CopyStrategy::Copy));
+    ///
+    /// write!(file, "modified")?;
+    /// assert_eq!(fs::read_to_string(fixture_path)?, "fixture contents");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn copy_of<P: AsRef<Utf8Path>>(src: P) -> io::Result<(NamedUtf8TempFile, CopyStrategy)> {
+        let src = src.as_ref();
+        let src_file = File::open(src).with_err_path(|| src)?;
+        let mut file = NamedUtf8TempFile::new()?;
+        let strategy =
+            copy_contents(&src_file, file.as_file_mut()).with_err_path(|| file.path())?;
+        file.seek(SeekFrom::Start(0))?;
+        Ok((file, strategy))
+    }
 }
 
 impl<F> NamedUtf8TempFile<F> {
@@ -676,10 +879,7 @@ impl<F> NamedUtf8TempFile<F> {
     /// ```
     #[inline]
     pub fn path(&self) -> &Utf8Path {
-        self.inner
-            .path()
-            .try_into()
-            .expect("invariant: path is valid UTF-8")
+        &self.path
     }
 
     /// Close and remove the temporary file.
@@ -714,7 +914,7 @@ impl<F> NamedUtf8TempFile<F> {
     /// # }
     /// ```
     pub fn close(self) -> io::Result<()> {
-        self.inner.close()
+        self.path.close()
     }
 
     /// Persist the temporary file at the target path.
@@ -762,13 +962,19 @@ impl<F> NamedUtf8TempFile<F> {
     ///
     /// [`PersistError`]: struct.PersistError.html
     pub fn persist<P: AsRef<Path>>(self, new_path: P) -> Result<F, Utf8PersistError<F>> {
-        self.inner.persist(new_path).map_err(|error| {
-            Utf8PersistError {
-                // This is valid because self is exactly error.file.
-                file: NamedUtf8TempFile { inner: error.file },
-                error: error.error,
-            }
-        })
+        let path = self.path.to_path_buf();
+        let new_path = new_path.as_ref();
+        let (inner, disable_cleanup) = self.into_temp_file();
+        let result = inner
+            .persist(new_path)
+            .map_err(|error| Self::persist_error(error, new_path, disable_cleanup));
+        trace::persist(
+            &path,
+            new_path,
+            false,
+            result.as_ref().err().map(|e| &e.error),
+        );
+        result
     }
 
     /// Persist the temporary file at the target path if and only if no file exists there.
@@ -814,13 +1020,19 @@ impl<F> NamedUtf8TempFile<F> {
     /// # }
     /// ```
     pub fn persist_noclobber<P: AsRef<Path>>(self, new_path: P) -> Result<F, Utf8PersistError<F>> {
-        self.inner.persist_noclobber(new_path).map_err(|error| {
-            Utf8PersistError {
-                // This is valid because self is exactly error.file.
-                file: NamedUtf8TempFile { inner: error.file },
-                error: error.error,
-            }
-        })
+        let path = self.path.to_path_buf();
+        let new_path = new_path.as_ref();
+        let (inner, disable_cleanup) = self.into_temp_file();
+        let result = inner
+            .persist_noclobber(new_path)
+            .map_err(|error| Self::persist_error(error, new_path, disable_cleanup));
+        trace::persist(
+            &path,
+            new_path,
+            true,
+            result.as_ref().err().map(|e| &e.error),
+        );
+        result
     }
 
     /// Keep the temporary file from being deleted. This function will turn the
@@ -854,16 +1066,19 @@ impl<F> NamedUtf8TempFile<F> {
     ///
     /// [`PathPersistError`]: struct.PathPersistError.html
     pub fn keep(self) -> Result<(F, Utf8PathBuf), Utf8PersistError<F>> {
-        match self.inner.keep() {
-            Ok((file, path)) => Ok((
-                file,
-                path.try_into().expect("invariant: path is valid UTF-8"),
-            )),
-            Err(error) => Err(Utf8PersistError {
-                // This is valid because self is exactly error.file.
-                file: NamedUtf8TempFile { inner: error.file },
-                error: error.error,
-            }),
+        let (inner, disable_cleanup) = self.into_temp_file();
+        match inner.keep() {
+            Ok((file, path)) => {
+                let path: Utf8PathBuf = path.try_into().expect("invariant: path is valid UTF-8");
+                trace::keep(EntryKind::File, &path, None);
+                Ok((file, path))
+            }
+            Err(error) => {
+                let path = error.file.path().to_owned();
+                let error = Self::persist_error(error, &path, disable_cleanup);
+                trace::keep(EntryKind::File, error.file.path(), Some(&error.error));
+                Err(error)
+            }
         }
     }
 
@@ -877,24 +1092,107 @@ impl<F> NamedUtf8TempFile<F> {
     /// want to simply turn a temporary file into a non-temporary file, prefer
     /// [`NamedUtf8TempFile::keep`].
     pub fn disable_cleanup(&mut self, disable_cleanup: bool) {
-        self.inner.disable_cleanup(disable_cleanup);
+        self.path.disable_cleanup(disable_cleanup);
+    }
+
+    /// Converts this `NamedUtf8TempFile` into the underlying [`NamedTempFile`].
+    ///
+    /// Whether cleanup is disabled carries over to the `NamedTempFile`. To go the other way, use
+    /// `NamedUtf8TempFile::try_from`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use camino_tempfile::NamedUtf8TempFile;
+    /// use std::convert::TryFrom;
+    ///
+    /// # fn main() -> std::io::Result<()> {
+    /// let file = NamedUtf8TempFile::new()?;
+    /// let path = file.path().to_owned();
+    ///
+    /// let inner: tempfile::NamedTempFile = file.into_inner();
+    /// assert_eq!(inner.path(), path);
+    ///
+    /// let file = NamedUtf8TempFile::try_from(inner).expect("path is valid UTF-8");
+    /// assert_eq!(file.path(), path);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_inner(self) -> NamedTempFile<F> {
+        self.into_temp_file().0
+    }
+
+    /// Set an extended attribute on the temporary file, replacing any existing value.
+    ///
+    /// Unprivileged processes can only set attributes in the `user.` namespace. To set attributes
+    /// as the file is created, use [`Builder::xattr`].
+    ///
+    /// This method is only available on Linux.
+    ///
+    /// # Errors
+    ///
+    /// If the filesystem doesn't support user extended attributes, an error with kind
+    /// [`io::ErrorKind::Unsupported`] is returned. Other errors, such as an invalid attribute
+    /// name, are returned as well.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use camino_tempfile::NamedUtf8TempFile;
+    /// use std::io;
+    ///
+    /// let file = NamedUtf8TempFile::new()?;
+    /// match file.set_xattr("user.origin", "https://example.com/artifact.tar.gz") {
+    ///     Ok(()) => {
+    ///         let xattrs = file.xattrs()?;
+    ///         assert!(xattrs.iter().any(|(name, _)| name == "user.origin"));
+    ///     }
+    ///     // Some filesystems, such as older versions of tmpfs, don't support
+    ///     // user extended attributes.
+    ///     Err(error) if error.kind() == io::ErrorKind::Unsupported => {}
+    ///     Err(error) => return Err(error),
+    /// }
+    /// # Ok::<(), std::io::Error>(())
+    /// ```
+    #[cfg(target_os = "linux")]
+    pub fn set_xattr<V: AsRef<[u8]>>(&self, name: &str, value: V) -> io::Result<()> {
+        crate::xattr::set(self.path(), name, value.as_ref())
+    }
+
+    /// Returns the extended attributes of the temporary file, along with their values.
+    ///
+    /// Only attributes that this process is allowed to read are returned. For unprivileged
+    /// processes, these are typically the ones in the `user.` namespace, along with some in the
+    /// `security.` namespace.
+    ///
+    /// This method is only available on Linux.
+    ///
+    /// # Errors
+    ///
+    /// If the attributes can't be read, `Err` is returned. If the name of an attribute is not
+    /// valid UTF-8, an error with kind [`io::ErrorKind::InvalidData`] is returned.
+    #[cfg(target_os = "linux")]
+    pub fn xattrs(&self) -> io::Result<Vec<(String, Vec<u8>)>> {
+        crate::xattr::list(self.path())
     }
 
     /// Get a reference to the underlying file.
     pub fn as_file(&self) -> &F {
-        self.inner.as_file()
+        &self.file
     }
 
     /// Get a mutable reference to the underlying file.
     pub fn as_file_mut(&mut self) -> &mut F {
-        self.inner.as_file_mut()
+        &mut self.file
     }
 
     /// Convert the temporary file into a `std::fs::File`.
     ///
     /// The inner file will be deleted.
     pub fn into_file(self) -> F {
-        self.inner.into_file()
+        let Self { path, file } = self;
+        drop(path);
+        file
     }
 
     /// Closes the file, leaving only the temporary file path.
@@ -902,8 +1200,9 @@ impl<F> NamedUtf8TempFile<F> {
     /// This is useful when another process must be able to open the temporary
     /// file.
     pub fn into_temp_path(self) -> Utf8TempPath {
-        Utf8TempPath::from_temp_path(self.inner.into_temp_path())
-            .expect("invariant: inner path is UTF-8")
+        let Self { path, file } = self;
+        drop(file);
+        path
     }
 
     /// Converts the named temporary file into its constituent parts.
@@ -911,9 +1210,7 @@ impl<F> NamedUtf8TempFile<F> {
     /// Note: When the path is dropped, the file is deleted but the file handle
     /// is still usable.
     pub fn into_parts(self) -> (F, Utf8TempPath) {
-        let (file, path) = self.inner.into_parts();
-        let path = Utf8TempPath::from_temp_path(path).expect("invariant: inner path is UTF-8");
-        (file, path)
+        (self.file, self.path)
     }
 
     /// Creates a `NamedUtf8TempFile` from its constituent parts.
@@ -921,9 +1218,114 @@ impl<F> NamedUtf8TempFile<F> {
     /// This can be used with [`NamedUtf8TempFile::into_parts`] to reconstruct the
     /// `NamedUtf8TempFile`.
     pub fn from_parts(file: F, path: Utf8TempPath) -> Self {
-        let inner = NamedTempFile::from_parts(file, path.inner);
-        // This is valid because it was constructed from a Utf8TempPath
-        Self { inner }
+        Self { path, file }
+    }
+
+    /// Wraps the file in a [`HashingWriter`], so that a digest of everything written to it is
+    /// computed along the way.
+    ///
+    /// Only data written after this call is included in the digest, so this is typically called
+    /// right after creating the file. Use [`NamedUtf8TempFile::persist_if_digest`] to persist the
+    /// file only if its digest matches an expected value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "sha256")]
+    /// # fn main() -> std::io::Result<()> {
+    /// use camino_tempfile::{NamedUtf8TempFile, Sha256Hasher};
+    /// use std::io::Write;
+    ///
+    /// let mut file = NamedUtf8TempFile::new()?.into_hashing(Sha256Hasher::new());
+    /// file.write_all(b"hello")?;
+    /// assert_eq!(
+    ///     file.as_file().digest().to_string(),
+    ///     "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824",
+    /// );
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "sha256"))]
+    /// # fn main() {}
+    /// ```
+    pub fn into_hashing<H: ContentHasher>(
+        self,
+        hasher: H,
+    ) -> NamedUtf8TempFile<HashingWriter<F, H>> {
+        let Self { path, file } = self;
+        NamedUtf8TempFile {
+            path,
+            file: HashingWriter::new(file, hasher),
+        }
+    }
+}
+
+impl<F, H: ContentHasher> NamedUtf8TempFile<HashingWriter<F, H>> {
+    /// Persist the temporary file at the target path, but only if the digest of the data written
+    /// to it matches `expected`.
+    ///
+    /// The digest is computed while writing (see [`NamedUtf8TempFile::into_hashing`]), so the file
+    /// is not read back. If the digests don't match, the file is not persisted, and is returned in
+    /// the resulting [`Utf8PersistError`] along with a [`DigestMismatchError`] that holds the
+    /// actual digest.
+    ///
+    /// Otherwise, this behaves like [`NamedUtf8TempFile::persist`].
+    ///
+    /// # Errors
+    ///
+    /// If the digests don't match, `Err` is returned with an [`io::Error`] of kind
+    /// [`io::ErrorKind::InvalidData`], which wraps a [`DigestMismatchError`].
+    ///
+    /// If the file cannot be moved to the new location, `Err` is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "sha256")]
+    /// # fn main() -> std::io::Result<()> {
+    /// use camino_tempfile::{
+    ///     Digest, DigestMismatchError, NamedUtf8TempFile, Sha256Hasher, Utf8TempDir,
+    /// };
+    /// use std::io::{self, Write};
+    ///
+    /// let dir = Utf8TempDir::new()?;
+    /// let expected: Digest = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+    ///     .parse()
+    ///     .unwrap();
+    ///
+    /// let mut file = NamedUtf8TempFile::new_in(dir.path())?.into_hashing(Sha256Hasher::new());
+    /// file.write_all(b"goodbye")?;
+    /// let error = file
+    ///     .persist_if_digest(&expected, dir.path().join("download"))
+    ///     .unwrap_err();
+    /// assert_eq!(error.error.kind(), io::ErrorKind::InvalidData);
+    /// let mismatch = error
+    ///     .error
+    ///     .get_ref()
+    ///     .and_then(|error| error.downcast_ref::<DigestMismatchError>())
+    ///     .expect("error is a DigestMismatchError");
+    /// assert_eq!(mismatch.actual(), &error.file.as_file().digest());
+    /// # Ok(())
+    /// # }
+    /// # #[cfg(not(feature = "sha256"))]
+    /// # fn main() {}
+    /// ```
+    pub fn persist_if_digest<P: AsRef<Path>>(
+        self,
+        expected: &Digest,
+        new_path: P,
+    ) -> Result<HashingWriter<F, H>, Utf8PersistError<HashingWriter<F, H>>> {
+        let actual = self.as_file().digest();
+        if actual != *expected {
+            let error = DigestMismatchError {
+                expected: expected.clone(),
+                actual,
+            };
+            return Err(Utf8PersistError {
+                error: io::Error::new(io::ErrorKind::InvalidData, error),
+                file: self,
+            });
+        }
+        self.persist(new_path)
     }
 }
 
@@ -963,7 +1365,13 @@ impl NamedUtf8TempFile<File> {
     /// # }
     /// ```
     pub fn reopen(&self) -> io::Result<File> {
-        self.inner.reopen()
+        // Build a `NamedTempFile` that borrows the file and path, so that the same-file checks
+        // in `NamedTempFile::reopen` can be used. Cleanup is disabled on the borrowed path, so
+        // dropping it afterwards doesn't delete anything.
+        let mut path = TempPath::from_path(self.path.to_path_buf());
+        path.disable_cleanup(true);
+        let file = self.file.try_clone().with_err_path(|| self.path())?;
+        NamedTempFile::from_parts(file, path).reopen()
     }
 }
 
@@ -0,0 +1,339 @@
+// Copyright (c) The camino-tempfile Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use std::{
+    error, fmt,
+    io::{self, Write},
+    str::FromStr,
+};
+
+/// A hash algorithm that can be fed data incrementally, used by
+/// [`HashingWriter`].
+///
+/// Implementations are provided for SHA-256 ([`Sha256Hasher`], with the
+/// `sha256` feature) and XXH3 ([`Xxh3Hasher`], with the `xxh3` feature). Other
+/// algorithms can be used by implementing this trait.
+pub trait ContentHasher {
+    /// Feeds `data` into the hasher.
+    fn update(&mut self, data: &[u8]);
+
+    /// Returns the digest of all the data fed in so far.
+    ///
+    /// This does not reset the hasher: more data can be fed in afterwards.
+    fn digest(&self) -> Digest;
+}
+
+/// A [`ContentHasher`] that computes SHA-256 digests.
+///
+/// This type is only available with the `sha256` feature enabled.
+#[cfg(feature = "sha256")]
+#[derive(Clone, Debug, Default)]
+pub struct Sha256Hasher {
+    inner: sha2::Sha256,
+}
+
+#[cfg(feature = "sha256")]
+impl Sha256Hasher {
+    /// Creates a new SHA-256 hasher.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[cfg(feature = "sha256")]
+impl ContentHasher for Sha256Hasher {
+    fn update(&mut self, data: &[u8]) {
+        sha2::Digest::update(&mut self.inner, data);
+    }
+
+    fn digest(&self) -> Digest {
+        Digest::from_bytes(sha2::Digest::finalize(self.inner.clone()).as_slice())
+    }
+}
+
+/// A [`ContentHasher`] that computes 64-bit XXH3 digests.
+///
+/// XXH3 is much faster than SHA-256, but is not a cryptographic hash: use it to
+/// detect accidental corruption, not tampering. Digests are 8 bytes long, in
+/// big-endian order, matching the canonical representation used by the
+/// `xxhsum` command-line tool.
+///
+/// This type is only available with the `xxh3` feature enabled.
+#[cfg(feature = "xxh3")]
+#[derive(Clone)]
+pub struct Xxh3Hasher {
+    inner: xxhash_rust::xxh3::Xxh3,
+}
+
+#[cfg(feature = "xxh3")]
+impl Xxh3Hasher {
+    /// Creates a new XXH3 hasher.
+    pub fn new() -> Self {
+        Self {
+            inner: xxhash_rust::xxh3::Xxh3::new(),
+        }
+    }
+}
+
+#[cfg(feature = "xxh3")]
+impl Default for Xxh3Hasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "xxh3")]
+impl fmt::Debug for Xxh3Hasher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Xxh3Hasher").finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "xxh3")]
+impl ContentHasher for Xxh3Hasher {
+    fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
+    }
+
+    fn digest(&self) -> Digest {
+        Digest::from_bytes(&self.inner.digest().to_be_bytes())
+    }
+}
+
+/// The digest computed by a [`ContentHasher`].
+///
+/// Digests are displayed and parsed as lowercase hexadecimal strings.
+///
+/// # Examples
+///
+/// ```
+/// use camino_tempfile::Digest;
+///
+/// let digest: Digest = "00ff10".parse().unwrap();
+/// assert_eq!(digest.as_bytes(), [0x00, 0xff, 0x10]);
+/// assert_eq!(digest.to_string(), "00ff10");
+/// ```
+#[derive(Clone, Eq, PartialEq, Hash)]
+pub struct Digest {
+    bytes: Box<[u8]>,
+}
+
+impl Digest {
+    /// Creates a digest from its raw bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        Self {
+            bytes: bytes.into(),
+        }
+    }
+
+    /// Returns the raw bytes of the digest.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl fmt::Display for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for byte in self.bytes.iter() {
+            write!(f, "{byte:02x}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for Digest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Digest({self})")
+    }
+}
+
+impl FromStr for Digest {
+    type Err = ParseDigestError;
+
+    /// Parses a digest from a hexadecimal string, in either case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.as_bytes();
+        if s.len() % 2 != 0 {
+            return Err(ParseDigestError);
+        }
+        s.chunks(2)
+            .map(|pair| Some(hex_value(pair[0])? << 4 | hex_value(pair[1])?))
+            .collect::<Option<Box<[u8]>>>()
+            .map(|bytes| Self { bytes })
+            .ok_or(ParseDigestError)
+    }
+}
+
+fn hex_value(c: u8) -> Option<u8> {
+    char::from(c).to_digit(16).map(|d| d as u8)
+}
+
+/// The error returned when parsing a [`Digest`] from a string that isn't valid
+/// hexadecimal.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[non_exhaustive]
+pub struct ParseDigestError;
+
+impl fmt::Display for ParseDigestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("digest is not a valid hexadecimal string")
+    }
+}
+
+impl error::Error for ParseDigestError {}
+
+/// A writer that computes a digest of everything written through it.
+///
+/// Wrap a temporary file in a `HashingWriter` with
+/// [`NamedUtf8TempFile::into_hashing`](crate::NamedUtf8TempFile::into_hashing),
+/// and then use
+/// [`NamedUtf8TempFile::persist_if_digest`](crate::NamedUtf8TempFile::persist_if_digest)
+/// to only persist the file if its contents match an expected digest, without
+/// reading the file back.
+///
+/// The digest covers exactly the bytes accepted by the inner writer. Bytes
+/// written through [`HashingWriter::get_mut`], or otherwise written to the
+/// underlying file, are not included. For that reason, `HashingWriter` doesn't
+/// implement [`Seek`](std::io::Seek) either.
+///
+/// # Examples
+///
+/// ```
+/// use camino_tempfile::{ContentHasher, Digest, HashingWriter};
+/// use std::io::Write;
+///
+/// // A (very weak) checksum, for illustration.
+/// #[derive(Default)]
+/// struct Sum(u8);
+///
+/// impl ContentHasher for Sum {
+///     fn update(&mut self, data: &[u8]) {
+///         for byte in data {
+///             self.0 = self.0.wrapping_add(*byte);
+///         }
+///     }
+///
+///     fn digest(&self) -> Digest {
+///         Digest::from_bytes(&[self.0])
+///     }
+/// }
+///
+/// let mut writer = HashingWriter::new(Vec::new(), Sum::default());
+/// writer.write_all(&[1, 2, 3])?;
+/// assert_eq!(writer.digest().as_bytes(), [6]);
+/// assert_eq!(writer.bytes_written(), 3);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct HashingWriter<W, H> {
+    inner: W,
+    hasher: H,
+    bytes_written: u64,
+}
+
+impl<W, H: ContentHasher> HashingWriter<W, H> {
+    /// Creates a new `HashingWriter` that writes to `inner` and feeds the
+    /// written data to `hasher`.
+    pub fn new(inner: W, hasher: H) -> Self {
+        Self {
+            inner,
+            hasher,
+            bytes_written: 0,
+        }
+    }
+
+    /// Returns the digest of all data written so far.
+    pub fn digest(&self) -> Digest {
+        self.hasher.digest()
+    }
+
+    /// Returns the number of bytes written so far.
+    #[inline]
+    pub fn bytes_written(&self) -> u64 {
+        self.bytes_written
+    }
+
+    /// Gets a reference to the hasher.
+    #[inline]
+    pub fn hasher(&self) -> &H {
+        &self.hasher
+    }
+
+    /// Gets a reference to the underlying writer.
+    #[inline]
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Gets a mutable reference to the underlying writer.
+    ///
+    /// Data written directly to the underlying writer is not included in the
+    /// digest.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Unwraps this `HashingWriter`, returning the underlying writer and the
+    /// hasher.
+    pub fn into_parts(self) -> (W, H) {
+        (self.inner, self.hasher)
+    }
+
+    /// Unwraps this `HashingWriter`, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write, H: ContentHasher> Write for HashingWriter<W, H> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.bytes_written += n as u64;
+        Ok(n)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// The error returned by
+/// [`NamedUtf8TempFile::persist_if_digest`](crate::NamedUtf8TempFile::persist_if_digest)
+/// if the digest of the file's contents doesn't match the expected digest.
+///
+/// This error is wrapped in an [`io::Error`] with kind
+/// [`io::ErrorKind::InvalidData`], and can be retrieved with
+/// [`io::Error::get_ref`] and `downcast_ref`.
+#[derive(Clone, Debug)]
+pub struct DigestMismatchError {
+    pub(crate) expected: Digest,
+    pub(crate) actual: Digest,
+}
+
+impl DigestMismatchError {
+    /// Returns the digest that was expected.
+    pub fn expected(&self) -> &Digest {
+        &self.expected
+    }
+
+    /// Returns the digest of the data actually written.
+    pub fn actual(&self) -> &Digest {
+        &self.actual
+    }
+}
+
+impl fmt::Display for DigestMismatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "digest mismatch: expected {}, actual {}",
+            self.expected, self.actual
+        )
+    }
+}
+
+impl error::Error for DigestMismatchError {}
@@ -1,9 +1,58 @@
 // Copyright (c) The camino-tempfile Contributors
 // SPDX-License-Identifier: MIT OR Apache-2.0
 
-use camino::Utf8PathBuf;
-use std::{convert::TryFrom, env, io};
+use crate::errors::non_utf8_error;
+use camino::{Utf8Path, Utf8PathBuf};
+use std::{convert::TryFrom, env, fs, io, path::Path};
 
 pub(crate) fn utf8_env_temp_dir() -> io::Result<Utf8PathBuf> {
-    Utf8PathBuf::try_from(env::temp_dir()).map_err(|error| error.into_io_error())
+    Utf8PathBuf::try_from(env::temp_dir()).map_err(|error| non_utf8_error(error.into_path_buf()))
+}
+
+/// Removes the directory at `path` along with all of its contents.
+///
+/// Tests often make parts of a temporary directory read-only. If removal fails
+/// because of that, write permissions are restored throughout the tree and
+/// removal is retried once.
+pub(crate) fn remove_dir_all(path: &Utf8Path) -> io::Result<()> {
+    match fs::remove_dir_all(path) {
+        Err(error) if error.kind() == io::ErrorKind::PermissionDenied => {
+            restore_permissions(path.as_std_path());
+            fs::remove_dir_all(path)
+        }
+        result => result,
+    }
+}
+
+/// Makes `path` and everything under it removable, without following symlinks.
+/// Errors are ignored: the retried removal reports anything that's still wrong.
+fn restore_permissions(path: &Path) {
+    let Ok(metadata) = fs::symlink_metadata(path) else {
+        return;
+    };
+    let mut permissions = metadata.permissions();
+
+    // On Unix, removing an entry depends on the permissions of the directory
+    // containing it. On Windows, it depends on the entry's read-only attribute.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+
+        if !metadata.is_dir() {
+            return;
+        }
+        permissions.set_mode(permissions.mode() | 0o700);
+    }
+    #[cfg(not(unix))]
+    #[allow(clippy::permissions_set_readonly_false)]
+    permissions.set_readonly(false);
+
+    let _ = fs::set_permissions(path, permissions);
+    if metadata.is_dir() {
+        if let Ok(entries) = fs::read_dir(path) {
+            for entry in entries.flatten() {
+                restore_permissions(&entry.path());
+            }
+        }
+    }
 }
@@ -176,15 +176,68 @@
 //! # Ok(())
 //! # }
 //! ```
+//!
+//! # Features
+//!
+//! - **memfd**: On Linux, enable [`MemfdUtf8TempFile`], an in-memory temporary
+//!   file that can be referred to by path. *Not enabled by default.*
+//! - **sha256**: Enable [`Sha256Hasher`], for computing SHA-256 digests of
+//!   temporary files while writing them. See
+//!   [`NamedUtf8TempFile::persist_if_digest`]. *Not enabled by default.*
+//! - **tracing**: Emit [`tracing`](https://docs.rs/tracing) events when
+//!   temporary files and directories are created, persisted, kept, and
+//!   removed, under the `camino_tempfile` target. Cleanup failures that would
+//!   otherwise be ignored, such as errors while removing a temporary directory
+//!   on drop, are reported at the `WARN` level; all other events are reported
+//!   at the `DEBUG` level. *Not enabled by default.*
+//! - **xxh3**: Enable [`Xxh3Hasher`], a fast non-cryptographic alternative to
+//!   [`Sha256Hasher`]. *Not enabled by default.*
 
 #![deny(rust_2018_idioms)]
+#![cfg_attr(doc_cfg, feature(doc_cfg))]
 
+mod background;
 mod builder;
+mod convert;
+mod copy;
 mod dir;
 mod errors;
+#[cfg(unix)]
+mod fifo;
 mod file;
+mod hashing;
 mod helpers;
+#[cfg(all(feature = "memfd", target_os = "linux"))]
+mod memfd;
+mod pool;
+#[cfg(unix)]
+mod process_shared;
+mod shared;
+#[cfg(unix)]
+mod socket;
+mod trace;
+#[cfg(target_os = "linux")]
+mod xattr;
 
+pub use background::wait_for_background_cleanup;
 pub use builder::*;
+pub use convert::FromTempfileError;
+pub use copy::{CloneTreeReport, CopyStrategy};
 pub use dir::*;
+pub use errors::{Utf8TempError, Utf8TempOperation};
+#[cfg(unix)]
+pub use fifo::Utf8TempFifo;
 pub use file::*;
+#[cfg(feature = "sha256")]
+pub use hashing::Sha256Hasher;
+#[cfg(feature = "xxh3")]
+pub use hashing::Xxh3Hasher;
+pub use hashing::{ContentHasher, Digest, DigestMismatchError, HashingWriter, ParseDigestError};
+#[cfg(all(feature = "memfd", target_os = "linux"))]
+pub use memfd::*;
+pub use pool::*;
+#[cfg(unix)]
+pub use process_shared::{JoinedUtf8TempDir, PROCESS_LOCK_FILE_NAME};
+pub use shared::SharedUtf8TempDir;
+#[cfg(unix)]
+pub use socket::{MAX_SOCKET_PATH_LEN, SocketPathTooLongError};
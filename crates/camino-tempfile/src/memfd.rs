@@ -0,0 +1,338 @@
+// Copyright (c) The camino-tempfile Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::errors::IoResultExt;
+use camino::{Utf8Path, Utf8PathBuf};
+use rustix::fs::{MemfdFlags, SealFlags};
+use std::{
+    fmt,
+    fs::File,
+    io::{self, Read, Seek, SeekFrom, Write},
+    ops,
+    os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd},
+    path::Path,
+};
+
+/// An anonymous, memory-backed temporary file created with
+/// [`memfd_create(2)`](https://man7.org/linux/man-pages/man2/memfd_create.2.html).
+///
+/// A `MemfdUtf8TempFile` never touches a filesystem on disk, and is freed by
+/// the kernel once the last handle to it is closed, regardless of whether Rust
+/// destructors run. Unlike [`tempfile()`](crate::tempfile), it can still be
+/// referred to by path: [`MemfdUtf8TempFile::path`] returns a path of the form
+/// `/proc/<pid>/fd/<fd>`, which other processes (such as children of the
+/// current process) can open while this file is alive.
+///
+/// Memfds are created with sealing allowed; see
+/// [`MemfdUtf8TempFile::add_seals`].
+///
+/// This type is only available on Linux, with the `memfd` feature enabled.
+///
+/// # Examples
+///
+/// Write some data into a memfd, then read it from a child process by path:
+///
+/// ```no_run
+/// use camino_tempfile::MemfdUtf8TempFile;
+/// use std::{io::Write, process::Command};
+///
+/// # fn main() -> std::io::Result<()> {
+/// let mut file = MemfdUtf8TempFile::new()?;
+/// writeln!(file, "Brian was here. Briefly.")?;
+///
+/// let output = Command::new("cat").arg(file.path()).output()?;
+/// assert_eq!(output.stdout, b"Brian was here. Briefly.\n");
+/// # Ok(())
+/// # }
+/// ```
+pub struct MemfdUtf8TempFile {
+    file: File,
+    path: Utf8PathBuf,
+}
+
+impl MemfdUtf8TempFile {
+    /// Create a new memfd-backed temporary file, named `camino-tempfile`.
+    ///
+    /// # Errors
+    ///
+    /// If the memfd cannot be created, `Err` is returned.
+    pub fn new() -> io::Result<Self> {
+        Self::with_name("camino-tempfile")
+    }
+
+    /// Create a new memfd-backed temporary file with the given name.
+    ///
+    /// The name is only used for debugging purposes: it shows up as the target
+    /// of the symlink at [`MemfdUtf8TempFile::path`], prefixed with `memfd:`.
+    /// Multiple memfds can have the same name.
+    ///
+    /// # Errors
+    ///
+    /// If the memfd cannot be created, `Err` is returned. In particular, the
+    /// name must be at most 249 bytes long and must not contain NUL bytes.
+    pub fn with_name<S: AsRef<str>>(name: S) -> io::Result<Self> {
+        let fd = rustix::fs::memfd_create(
+            name.as_ref(),
+            MemfdFlags::CLOEXEC | MemfdFlags::ALLOW_SEALING,
+        )?;
+        let file = File::from(fd);
+        let path = Utf8PathBuf::from(format!(
+            "/proc/{}/fd/{}",
+            std::process::id(),
+            file.as_raw_fd()
+        ));
+        Ok(Self { file, path })
+    }
+
+    /// Get a path to the memfd.
+    ///
+    /// The path is of the form `/proc/<pid>/fd/<fd>`, and can be used by
+    /// any process with access to this process's `/proc` entry (generally,
+    /// processes running as the same user) to open the file, for as long as
+    /// this `MemfdUtf8TempFile` is alive.
+    ///
+    /// Note that opening the path produces a new, independent file
+    /// description. Seals still apply to it.
+    #[inline]
+    #[must_use]
+    pub fn path(&self) -> &Utf8Path {
+        &self.path
+    }
+
+    /// Get a reference to the underlying file.
+    pub fn as_file(&self) -> &File {
+        &self.file
+    }
+
+    /// Get a mutable reference to the underlying file.
+    pub fn as_file_mut(&mut self) -> &mut File {
+        &mut self.file
+    }
+
+    /// Convert the memfd into a `std::fs::File`.
+    ///
+    /// The memory backing the file is freed once the last handle to it is
+    /// closed.
+    pub fn into_file(self) -> File {
+        self.file
+    }
+
+    /// Open an independent handle to the memfd, via its path.
+    ///
+    /// # Errors
+    ///
+    /// If the memfd cannot be reopened, `Err` is returned.
+    pub fn reopen(&self) -> io::Result<File> {
+        File::options()
+            .read(true)
+            .write(true)
+            .open(&self.path)
+            .with_err_path(|| self.path())
+    }
+
+    /// Add seals to the memfd.
+    ///
+    /// Seals restrict the operations that can be performed on the file, by
+    /// any process. Once added, seals cannot be removed. See
+    /// [`MemfdSeals`] for the available seals.
+    ///
+    /// # Errors
+    ///
+    /// If the seals cannot be added, `Err` is returned. For example, adding
+    /// [`MemfdSeals::WRITE`] fails with [`io::ErrorKind::ResourceBusy`] if the
+    /// file is currently memory-mapped as shared and writable, and adding any
+    /// seal fails with [`io::ErrorKind::PermissionDenied`] if
+    /// [`MemfdSeals::SEAL`] has been added before.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use camino_tempfile::{MemfdSeals, MemfdUtf8TempFile};
+    /// use std::io::Write;
+    ///
+    /// # fn main() -> std::io::Result<()> {
+    /// let mut file = MemfdUtf8TempFile::new()?;
+    /// file.write_all(b"read-only from here on")?;
+    ///
+    /// file.add_seals(MemfdSeals::WRITE | MemfdSeals::SHRINK | MemfdSeals::GROW)?;
+    /// assert!(file.seals()?.contains(MemfdSeals::WRITE));
+    /// assert!(file.write_all(b"more").is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn add_seals(&self, seals: MemfdSeals) -> io::Result<()> {
+        rustix::fs::fcntl_add_seals(&self.file, seals.to_rustix())
+            .map_err(io::Error::from)
+            .with_err_path(|| self.path())
+    }
+
+    /// Return the seals currently applied to the memfd.
+    ///
+    /// # Errors
+    ///
+    /// If the seals cannot be retrieved, `Err` is returned.
+    pub fn seals(&self) -> io::Result<MemfdSeals> {
+        let seals = rustix::fs::fcntl_get_seals(&self.file)
+            .map_err(io::Error::from)
+            .with_err_path(|| self.path())?;
+        Ok(MemfdSeals::from_rustix(seals))
+    }
+}
+
+impl fmt::Debug for MemfdUtf8TempFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "MemfdUtf8TempFile({})", self.path)
+    }
+}
+
+impl AsRef<Utf8Path> for MemfdUtf8TempFile {
+    #[inline]
+    fn as_ref(&self) -> &Utf8Path {
+        self.path()
+    }
+}
+
+impl AsRef<Path> for MemfdUtf8TempFile {
+    #[inline]
+    fn as_ref(&self) -> &Path {
+        self.path().as_std_path()
+    }
+}
+
+impl Read for MemfdUtf8TempFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.file.read(buf).with_err_path(|| self.path())
+    }
+}
+
+impl Read for &MemfdUtf8TempFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.as_file().read(buf).with_err_path(|| self.path())
+    }
+}
+
+impl Write for MemfdUtf8TempFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.file.write(buf).with_err_path(|| self.path())
+    }
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush().with_err_path(|| self.path())
+    }
+}
+
+impl Write for &MemfdUtf8TempFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.as_file().write(buf).with_err_path(|| self.path())
+    }
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.as_file().flush().with_err_path(|| self.path())
+    }
+}
+
+impl Seek for MemfdUtf8TempFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.file.seek(pos).with_err_path(|| self.path())
+    }
+}
+
+impl Seek for &MemfdUtf8TempFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.as_file().seek(pos).with_err_path(|| self.path())
+    }
+}
+
+impl AsRawFd for MemfdUtf8TempFile {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.file.as_raw_fd()
+    }
+}
+
+impl AsFd for MemfdUtf8TempFile {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.file.as_fd()
+    }
+}
+
+/// A set of seals that can be applied to a [`MemfdUtf8TempFile`].
+///
+/// Seals can be combined with `|`. See
+/// [`fcntl(2)`](https://man7.org/linux/man-pages/man2/fcntl.2.html) for more
+/// about file sealing.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub struct MemfdSeals(u32);
+
+impl MemfdSeals {
+    /// `F_SEAL_SEAL`: prevent any further seals from being added.
+    pub const SEAL: Self = Self(SealFlags::SEAL.bits());
+
+    /// `F_SEAL_SHRINK`: prevent the file from being shrunk.
+    pub const SHRINK: Self = Self(SealFlags::SHRINK.bits());
+
+    /// `F_SEAL_GROW`: prevent the file from being grown, whether by writing
+    /// past its end or by truncating it to a larger size.
+    pub const GROW: Self = Self(SealFlags::GROW.bits());
+
+    /// `F_SEAL_WRITE`: prevent the contents of the file from being modified.
+    pub const WRITE: Self = Self(SealFlags::WRITE.bits());
+
+    /// `F_SEAL_FUTURE_WRITE`: prevent new writable mappings and writes, while
+    /// allowing existing shared writable mappings to keep modifying the file.
+    /// Requires Linux 5.1 or newer.
+    pub const FUTURE_WRITE: Self = Self(SealFlags::FUTURE_WRITE.bits());
+
+    /// Returns an empty set of seals.
+    #[inline]
+    #[must_use]
+    pub const fn empty() -> Self {
+        Self(0)
+    }
+
+    /// Returns true if no seals are set.
+    #[inline]
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns true if all seals in `other` are also set in `self`.
+    #[inline]
+    #[must_use]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns the raw `F_SEAL_*` bits.
+    #[inline]
+    #[must_use]
+    pub const fn bits(self) -> u32 {
+        self.0
+    }
+
+    fn to_rustix(self) -> SealFlags {
+        SealFlags::from_bits_retain(self.0)
+    }
+
+    fn from_rustix(seals: SealFlags) -> Self {
+        Self(seals.bits())
+    }
+}
+
+impl ops::BitOr for MemfdSeals {
+    type Output = Self;
+
+    #[inline]
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl ops::BitOrAssign for MemfdSeals {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
@@ -0,0 +1,338 @@
+// Copyright (c) The camino-tempfile Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::{Builder, Utf8TempDir, Utf8TempOperation, errors::IoResultExt, helpers};
+use camino::{Utf8Path, Utf8PathBuf};
+use std::{
+    fmt, fs, io,
+    path::Path,
+    sync::{Arc, Mutex, mpsc},
+    thread,
+};
+
+/// A pool of reusable temporary directories.
+///
+/// Creating and deleting a [`Utf8TempDir`] is relatively expensive. In hot
+/// loops such as benchmarks, this cost can dominate the work actually being
+/// measured. A `Utf8TempDirPool` amortizes this cost by handing out
+/// [`Utf8TempDirLease`]s to directories that are emptied and recycled once the
+/// lease is dropped, rather than deleted.
+///
+/// Directories are created with [`Builder::tempdir_in`] as needed. All
+/// directories owned by the pool are deleted when the pool is dropped; use
+/// [`Utf8TempDirPool::close`] to detect errors while deleting them.
+///
+/// By default, directories are emptied on the thread that drops the lease.
+/// Call [`Utf8TempDirPool::background_cleanup`] to empty them on a background
+/// thread instead.
+///
+/// # Examples
+///
+/// ```
+/// use camino_tempfile::Utf8TempDirPool;
+/// use std::fs;
+///
+/// # fn main() -> std::io::Result<()> {
+/// let pool = Utf8TempDirPool::new()?;
+///
+/// let first_path = {
+///     let lease = pool.lease()?;
+///     fs::write(lease.path().join("output.txt"), "Brian was here. Briefly.")?;
+///     lease.path().to_owned()
+/// };
+///
+/// // The directory is emptied and handed out again.
+/// let lease = pool.lease()?;
+/// assert_eq!(lease.path(), first_path);
+/// assert!(!lease.path().join("output.txt").exists());
+/// # Ok(())
+/// # }
+/// ```
+pub struct Utf8TempDirPool<'a, 'b> {
+    builder: Builder<'a, 'b>,
+    dir: Utf8PathBuf,
+    idle: Arc<Mutex<Vec<Utf8TempDir>>>,
+    worker: Option<Worker>,
+}
+
+impl Utf8TempDirPool<'static, 'static> {
+    /// Create a new pool of temporary directories inside of
+    /// [`std::env::temp_dir()`].
+    ///
+    /// No directories are created until [`Utf8TempDirPool::lease`] is called.
+    ///
+    /// # Errors
+    ///
+    /// If [`std::env::temp_dir()`] is non-UTF-8, `Err` is returned.
+    pub fn new() -> io::Result<Self> {
+        Builder::new().tempdir_pool()
+    }
+
+    /// Create a new pool of temporary directories inside of `dir`.
+    ///
+    /// No directories are created until [`Utf8TempDirPool::lease`] is called.
+    pub fn new_in<P: AsRef<Utf8Path>>(dir: P) -> Self {
+        Builder::new().tempdir_pool_in(dir)
+    }
+}
+
+impl<'a, 'b> Utf8TempDirPool<'a, 'b> {
+    pub(crate) fn from_builder(builder: Builder<'a, 'b>, dir: Utf8PathBuf) -> Self {
+        Self {
+            builder,
+            dir,
+            idle: Arc::new(Mutex::new(Vec::new())),
+            worker: None,
+        }
+    }
+
+    /// Returns the directory that temporary directories are created inside of.
+    #[must_use]
+    pub fn dir(&self) -> &Utf8Path {
+        &self.dir
+    }
+
+    /// Empty returned directories on a background thread.
+    ///
+    /// If `background_cleanup` is `true`, dropping a [`Utf8TempDirLease`]
+    /// hands its directory off to a worker thread, which empties it and then
+    /// makes it available for reuse. This takes the cost of deleting the
+    /// directory's contents off the thread that dropped the lease.
+    ///
+    /// If `background_cleanup` is `false` (the default), directories are
+    /// emptied synchronously when the lease is dropped. Switching background
+    /// cleanup off waits for any pending work on the worker thread to finish.
+    ///
+    /// # Errors
+    ///
+    /// If the worker thread cannot be spawned, `Err` is returned and background
+    /// cleanup stays off.
+    pub fn background_cleanup(&mut self, background_cleanup: bool) -> io::Result<&mut Self> {
+        match (background_cleanup, self.worker.is_some()) {
+            (true, false) => self.worker = Some(Worker::spawn(self.idle.clone())?),
+            (false, true) => self.worker = None,
+            _ => {}
+        }
+        Ok(self)
+    }
+
+    /// Lease a temporary directory from the pool.
+    ///
+    /// An idle directory is reused if one is available; otherwise, a new one is
+    /// created with [`Builder::tempdir_in`]. The directory is empty when
+    /// handed out.
+    ///
+    /// # Errors
+    ///
+    /// If a new directory needs to be created and it cannot be, `Err` is
+    /// returned.
+    pub fn lease(&self) -> io::Result<Utf8TempDirLease<'_, 'a, 'b>> {
+        let idle = self.idle.lock().expect("idle list lock poisoned").pop();
+        let dir = match idle {
+            Some(dir) => dir,
+            None => self.builder.tempdir_in(&self.dir)?,
+        };
+        Ok(Utf8TempDirLease {
+            pool: self,
+            dir: Some(dir),
+        })
+    }
+
+    /// Returns the number of directories that are currently idle, waiting to be
+    /// handed out.
+    ///
+    /// Directories that are still being emptied in the background are not
+    /// counted.
+    #[must_use]
+    pub fn idle_count(&self) -> usize {
+        self.idle.lock().expect("idle list lock poisoned").len()
+    }
+
+    /// Closes the pool and removes all of its directories, returning a
+    /// `Result`.
+    ///
+    /// This waits for any pending background cleanup to finish. Although the
+    /// pool removes its directories on drop, in the destructor any errors are
+    /// ignored. To detect errors cleaning up the directories, call `close`
+    /// instead.
+    ///
+    /// # Errors
+    ///
+    /// If any of the directories cannot be removed, the first error
+    /// encountered is returned. An attempt is made to remove every directory
+    /// regardless.
+    pub fn close(mut self) -> io::Result<()> {
+        // Dropping the worker waits for the background thread to finish.
+        self.worker = None;
+        let idle = std::mem::take(&mut *self.idle.lock().expect("idle list lock poisoned"));
+        let mut result = Ok(());
+        for dir in idle {
+            let res = dir.close();
+            if result.is_ok() {
+                result = res;
+            }
+        }
+        result
+    }
+
+    fn recycle(&self, dir: Utf8TempDir) {
+        match &self.worker {
+            Some(worker) => worker.send(dir),
+            None => recycle_into(&self.idle, dir),
+        }
+    }
+}
+
+impl fmt::Debug for Utf8TempDirPool<'_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Utf8TempDirPool")
+            .field("builder", &self.builder)
+            .field("dir", &self.dir)
+            .field("idle_count", &self.idle_count())
+            .field("background_cleanup", &self.worker.is_some())
+            .finish()
+    }
+}
+
+impl Drop for Utf8TempDirPool<'_, '_> {
+    fn drop(&mut self) {
+        // Wait for the worker to finish before the idle directories are
+        // dropped, so that directories being emptied are also removed.
+        self.worker = None;
+    }
+}
+
+/// A temporary directory leased from a [`Utf8TempDirPool`].
+///
+/// When the lease is dropped, the directory is emptied and returned to the
+/// pool for reuse. If the directory cannot be emptied, it is deleted instead.
+///
+/// It's important to ensure that handles to files inside the directory are
+/// dropped before the lease is.
+pub struct Utf8TempDirLease<'pool, 'a, 'b> {
+    pool: &'pool Utf8TempDirPool<'a, 'b>,
+    // Invariant: this is always `Some` until the lease is dropped or consumed.
+    dir: Option<Utf8TempDir>,
+}
+
+impl Utf8TempDirLease<'_, '_, '_> {
+    /// Accesses the [`Utf8Path`] to the leased directory.
+    #[must_use]
+    pub fn path(&self) -> &Utf8Path {
+        self.dir
+            .as_ref()
+            .expect("invariant: dir is Some until dropped")
+            .path()
+    }
+
+    /// Removes the directory from the pool, turning it into a regular
+    /// [`Utf8TempDir`].
+    ///
+    /// The returned directory is deleted when it is dropped, and is not
+    /// returned to the pool.
+    #[must_use]
+    pub fn into_temp_dir(mut self) -> Utf8TempDir {
+        self.dir
+            .take()
+            .expect("invariant: dir is Some until dropped")
+    }
+}
+
+impl AsRef<Utf8Path> for Utf8TempDirLease<'_, '_, '_> {
+    #[inline]
+    fn as_ref(&self) -> &Utf8Path {
+        self.path()
+    }
+}
+
+impl AsRef<Path> for Utf8TempDirLease<'_, '_, '_> {
+    #[inline]
+    fn as_ref(&self) -> &Path {
+        self.path().as_std_path()
+    }
+}
+
+impl fmt::Debug for Utf8TempDirLease<'_, '_, '_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Utf8TempDirLease")
+            .field("path", &self.path())
+            .finish()
+    }
+}
+
+impl Drop for Utf8TempDirLease<'_, '_, '_> {
+    fn drop(&mut self) {
+        if let Some(dir) = self.dir.take() {
+            self.pool.recycle(dir);
+        }
+    }
+}
+
+struct Worker {
+    sender: Option<mpsc::Sender<Utf8TempDir>>,
+    handle: Option<thread::JoinHandle<()>>,
+}
+
+impl Worker {
+    fn spawn(idle: Arc<Mutex<Vec<Utf8TempDir>>>) -> io::Result<Self> {
+        let (sender, receiver) = mpsc::channel::<Utf8TempDir>();
+        let handle = thread::Builder::new()
+            .name("camino-tempfile-pool".to_owned())
+            .spawn(move || {
+                for dir in receiver {
+                    recycle_into(&idle, dir);
+                }
+            })?;
+        Ok(Self {
+            sender: Some(sender),
+            handle: Some(handle),
+        })
+    }
+
+    fn send(&self, dir: Utf8TempDir) {
+        let sender = self.sender.as_ref().expect("sender is only taken on drop");
+        // If the worker thread has gone away, the directory is returned here
+        // and dropped, which deletes it.
+        let _ = sender.send(dir);
+    }
+}
+
+impl Drop for Worker {
+    fn drop(&mut self) {
+        // Dropping the sender causes the worker loop to exit once all pending
+        // directories have been processed.
+        self.sender = None;
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+fn recycle_into(idle: &Mutex<Vec<Utf8TempDir>>, dir: Utf8TempDir) {
+    // If the directory can't be emptied, drop it instead, deleting it.
+    if remove_dir_contents(dir.path()).is_ok() {
+        idle.lock().expect("idle list lock poisoned").push(dir);
+    }
+}
+
+fn remove_dir_contents(dir: &Utf8Path) -> io::Result<()> {
+    for entry in dir
+        .read_dir_utf8()
+        .with_err_op(Utf8TempOperation::Cleanup, || dir)?
+    {
+        let entry = entry.with_err_op(Utf8TempOperation::Cleanup, || dir)?;
+        let path = entry.path();
+        // `file_type` does not follow symlinks, so symlinks to directories are
+        // removed rather than traversed.
+        if entry
+            .file_type()
+            .with_err_op(Utf8TempOperation::Cleanup, || path)?
+            .is_dir()
+        {
+            helpers::remove_dir_all(path).with_err_op(Utf8TempOperation::Cleanup, || path)?;
+        } else {
+            fs::remove_file(path).with_err_op(Utf8TempOperation::Cleanup, || path)?;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,223 @@
+// Copyright (c) The camino-tempfile Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::{
+    Utf8TempOperation,
+    errors::IoResultExt,
+    helpers,
+    trace::{self, EntryKind},
+};
+use camino::{Utf8Path, Utf8PathBuf};
+use std::{
+    fmt,
+    fs::{self, File},
+    io,
+    os::unix::{fs::MetadataExt, io::AsRawFd},
+    path::Path,
+};
+
+/// The name of the marker file that processes sharing a temporary directory
+/// hold locks on.
+pub const PROCESS_LOCK_FILE_NAME: &str = ".camino-tempfile.lock";
+
+/// A shared `flock` on the marker file in a temporary directory shared across
+/// processes.
+pub(crate) struct ProcessLock {
+    file: File,
+    // The path to the marker file.
+    path: Utf8PathBuf,
+}
+
+impl ProcessLock {
+    /// Creates the marker file in the newly created directory `dir`, and takes
+    /// a shared lock on it.
+    pub(crate) fn create(dir: &Utf8Path) -> io::Result<Self> {
+        let path = dir.join(PROCESS_LOCK_FILE_NAME);
+        let file = File::options()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .with_err_path(|| &path)?;
+        flock(&file, libc::LOCK_SH).with_err_path(|| &path)?;
+        Ok(Self { file, path })
+    }
+
+    /// Takes a shared lock on the marker file in the existing directory `dir`.
+    ///
+    /// This blocks while another process holds the exclusive lock, which only
+    /// happens while it's removing the directory.
+    pub(crate) fn join(dir: &Utf8Path) -> io::Result<Self> {
+        let path = dir.join(PROCESS_LOCK_FILE_NAME);
+        let file = File::open(&path).with_err_path(|| &path)?;
+        flock(&file, libc::LOCK_SH).with_err_path(|| &path)?;
+
+        // The last user may have removed the directory between the open and
+        // the lock above. In that case, the marker file is no longer at `path`.
+        let lock = Self { file, path };
+        if lock.is_current().with_err_path(|| &lock.path)? {
+            Ok(lock)
+        } else {
+            Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "temporary directory was removed while joining it",
+            ))
+            .with_err_path(|| dir)
+        }
+    }
+
+    /// Returns true if the locked file is still the marker file in the
+    /// directory, rather than one that's been removed along with it.
+    fn is_current(&self) -> io::Result<bool> {
+        let locked = self.file.metadata()?;
+        match fs::metadata(&self.path) {
+            Ok(current) => Ok(current.dev() == locked.dev() && current.ino() == locked.ino()),
+            Err(_) => Ok(false),
+        }
+    }
+
+    /// Releases the shared lock.
+    ///
+    /// If no other process holds a lock, returns a lock that is held
+    /// exclusively, meaning that the caller is the last user and should remove
+    /// the directory before dropping it.
+    pub(crate) fn release(self) -> Option<Self> {
+        // Unlock explicitly first: lock conversions aren't atomic anyway, and
+        // this makes sure that if two processes release at the same time, at
+        // least one of them acquires the exclusive lock.
+        flock(&self.file, libc::LOCK_UN).ok()?;
+        flock(&self.file, libc::LOCK_EX | libc::LOCK_NB).ok()?;
+        // Another process may have taken the exclusive lock between the two
+        // calls above, removed the directory, and released the lock again.
+        // In that case, there's nothing left to remove.
+        self.is_current().unwrap_or(false).then_some(self)
+    }
+}
+
+fn flock(file: &File, operation: libc::c_int) -> io::Result<()> {
+    // SAFETY: the file descriptor is valid for the duration of this call.
+    let ret = unsafe { libc::flock(file.as_raw_fd(), operation) };
+    if ret == 0 {
+        Ok(())
+    } else {
+        Err(io::Error::last_os_error())
+    }
+}
+
+/// A handle to a temporary directory created by another process with
+/// [`Builder::process_shared`](crate::Builder::process_shared).
+///
+/// Each process using the directory, including the one that created it, holds
+/// a shared `flock(2)` on a marker file named [`PROCESS_LOCK_FILE_NAME`] inside
+/// the directory. When a process is done with the directory, it releases its
+/// lock, and the last process to do so removes the directory.
+///
+/// This type is only available on Unix.
+///
+/// # Examples
+///
+/// ```
+/// use camino_tempfile::{Builder, JoinedUtf8TempDir};
+///
+/// # fn main() -> std::io::Result<()> {
+/// // In the parent process:
+/// let dir = Builder::new().process_shared(true).tempdir()?;
+///
+/// // In a child process, given the path, for example through an environment
+/// // variable:
+/// let joined = JoinedUtf8TempDir::join(dir.path())?;
+///
+/// // The directory is removed once both handles are dropped, in either order.
+/// let path = dir.path().to_owned();
+/// drop(dir);
+/// assert!(path.exists());
+/// drop(joined);
+/// assert!(!path.exists());
+/// # Ok(())
+/// # }
+/// ```
+pub struct JoinedUtf8TempDir {
+    path: Utf8PathBuf,
+    // None once the lock has been released.
+    lock: Option<ProcessLock>,
+}
+
+impl JoinedUtf8TempDir {
+    /// Joins the temporary directory at `path`, which must have been created
+    /// with [`Builder::process_shared`](crate::Builder::process_shared).
+    ///
+    /// # Errors
+    ///
+    /// If the directory doesn't exist, wasn't created with
+    /// [`Builder::process_shared`](crate::Builder::process_shared), or was
+    /// removed by the last user while joining, an error with kind
+    /// [`io::ErrorKind::NotFound`] is returned.
+    pub fn join<P: AsRef<Utf8Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let lock = ProcessLock::join(path)?;
+        Ok(Self {
+            path: path.to_owned(),
+            lock: Some(lock),
+        })
+    }
+
+    /// Accesses the [`Utf8Path`] to the temporary directory.
+    #[must_use]
+    pub fn path(&self) -> &Utf8Path {
+        &self.path
+    }
+
+    /// Releases this process's lock on the directory, removing the directory
+    /// if this was the last process using it.
+    ///
+    /// Use this if you want to detect errors while removing the directory.
+    ///
+    /// # Errors
+    ///
+    /// If this was the last process using the directory and it can not be
+    /// removed, `Err` is returned.
+    pub fn close(mut self) -> io::Result<()> {
+        let result = self.release();
+        if let Some(result) = &result {
+            trace::close(EntryKind::Dir, &self.path, result);
+        }
+        result.unwrap_or(Ok(()))
+    }
+
+    fn release(&mut self) -> Option<io::Result<()>> {
+        let exclusive = self.lock.take()?.release()?;
+        let result = helpers::remove_dir_all(&self.path)
+            .with_err_op(Utf8TempOperation::Cleanup, || &self.path);
+        drop(exclusive);
+        Some(result)
+    }
+}
+
+impl AsRef<Utf8Path> for JoinedUtf8TempDir {
+    #[inline]
+    fn as_ref(&self) -> &Utf8Path {
+        self.path()
+    }
+}
+
+impl AsRef<Path> for JoinedUtf8TempDir {
+    #[inline]
+    fn as_ref(&self) -> &Path {
+        self.path().as_std_path()
+    }
+}
+
+impl fmt::Debug for JoinedUtf8TempDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JoinedUtf8TempDir")
+            .field("path", &self.path())
+            .finish()
+    }
+}
+
+impl Drop for JoinedUtf8TempDir {
+    fn drop(&mut self) {
+        if let Some(result) = self.release() {
+            trace::drop_cleanup(EntryKind::Dir, &self.path, &result);
+        }
+    }
+}
@@ -0,0 +1,145 @@
+// Copyright (c) The camino-tempfile Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::Utf8TempDir;
+use camino::Utf8Path;
+use std::{fmt, io, path::Path, sync::Arc};
+
+/// A reference-counted handle to a temporary directory, which can be shared
+/// across threads.
+///
+/// Cloning a `SharedUtf8TempDir` produces another handle to the same
+/// directory. The directory is deleted when the last handle is dropped.
+///
+/// Unlike wrapping a [`Utf8TempDir`] in an [`Arc`], the last owner can still
+/// detect errors while deleting the directory, through
+/// [`SharedUtf8TempDir::try_close`], or take back the underlying `Utf8TempDir`
+/// with [`SharedUtf8TempDir::try_unwrap`].
+///
+/// # Examples
+///
+/// ```
+/// use camino_tempfile::SharedUtf8TempDir;
+/// use std::{fs, thread};
+///
+/// # fn main() -> std::io::Result<()> {
+/// let dir = SharedUtf8TempDir::new()?;
+///
+/// let workers: Vec<_> = (0..4)
+///     .map(|i| {
+///         let dir = dir.clone();
+///         thread::spawn(move || fs::write(dir.path().join(format!("{i}.txt")), "done"))
+///     })
+///     .collect();
+/// for worker in workers {
+///     worker.join().unwrap()?;
+/// }
+///
+/// // All other handles have been dropped, so this is the last owner.
+/// dir.try_close().expect("this is the last owner")?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct SharedUtf8TempDir {
+    inner: Arc<Utf8TempDir>,
+}
+
+impl SharedUtf8TempDir {
+    /// Attempts to make a temporary directory inside of [`std::env::temp_dir()`], and returns a
+    /// shared handle to it.
+    ///
+    /// See [`Utf8TempDir::new`] for more.
+    ///
+    /// # Errors
+    ///
+    /// If the directory can not be created, `Err` is returned.
+    pub fn new() -> io::Result<Self> {
+        Utf8TempDir::new().map(Self::from)
+    }
+
+    /// Attempts to make a temporary directory inside of `dir`, and returns a shared handle to it.
+    ///
+    /// See [`Utf8TempDir::new_in`] for more.
+    ///
+    /// # Errors
+    ///
+    /// If the directory can not be created, `Err` is returned.
+    pub fn new_in<P: AsRef<Utf8Path>>(dir: P) -> io::Result<Self> {
+        Utf8TempDir::new_in(dir).map(Self::from)
+    }
+
+    /// Accesses the [`Utf8Path`] to the temporary directory.
+    #[must_use]
+    pub fn path(&self) -> &Utf8Path {
+        self.inner.path()
+    }
+
+    /// Returns the number of handles to this directory, including this one.
+    ///
+    /// Other threads can clone or drop handles concurrently, so the returned
+    /// value may be out of date by the time it is used.
+    #[must_use]
+    pub fn handle_count(&self) -> usize {
+        Arc::strong_count(&self.inner)
+    }
+
+    /// Returns the underlying [`Utf8TempDir`] if this is the last handle to
+    /// the directory, or this handle otherwise.
+    ///
+    /// # Errors
+    ///
+    /// If other handles to the directory exist, this handle is returned
+    /// unchanged.
+    pub fn try_unwrap(self) -> Result<Utf8TempDir, Self> {
+        Arc::try_unwrap(self.inner).map_err(|inner| Self { inner })
+    }
+
+    /// Closes and removes the temporary directory if this is the last handle to
+    /// it, returning the result of removing it.
+    ///
+    /// This is equivalent to calling [`Utf8TempDir::close`] on the result of
+    /// [`SharedUtf8TempDir::try_unwrap`].
+    ///
+    /// # Errors
+    ///
+    /// If other handles to the directory exist, the outer `Result` is `Err`
+    /// with this handle unchanged, and the directory is not removed.
+    ///
+    /// Otherwise, the inner `Result` is the result of removing the directory;
+    /// see [`Utf8TempDir::close`].
+    pub fn try_close(self) -> Result<io::Result<()>, Self> {
+        self.try_unwrap().map(Utf8TempDir::close)
+    }
+}
+
+impl From<Utf8TempDir> for SharedUtf8TempDir {
+    fn from(dir: Utf8TempDir) -> Self {
+        Self {
+            inner: Arc::new(dir),
+        }
+    }
+}
+
+impl AsRef<Utf8Path> for SharedUtf8TempDir {
+    #[inline]
+    fn as_ref(&self) -> &Utf8Path {
+        self.path()
+    }
+}
+
+impl AsRef<Path> for SharedUtf8TempDir {
+    #[inline]
+    fn as_ref(&self) -> &Path {
+        self.path().as_std_path()
+    }
+}
+
+impl fmt::Debug for SharedUtf8TempDir {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SharedUtf8TempDir")
+            .field("path", &self.path())
+            .field("handle_count", &self.handle_count())
+            .finish()
+    }
+}
@@ -0,0 +1,153 @@
+// Copyright (c) The camino-tempfile Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::helpers::utf8_env_temp_dir;
+use camino::{Utf8Path, Utf8PathBuf};
+use std::{env, error, fmt, io};
+
+/// The maximum length, in bytes, of a Unix domain socket path on this platform.
+///
+/// This is one less than the size of `sun_path` in `sockaddr_un`, to leave
+/// room for the trailing NUL byte.
+#[cfg(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "openbsd",
+    target_os = "netbsd",
+))]
+pub const MAX_SOCKET_PATH_LEN: usize = 103;
+
+/// The maximum length, in bytes, of a Unix domain socket path on this platform.
+///
+/// This is one less than the size of `sun_path` in `sockaddr_un`, to leave
+/// room for the trailing NUL byte.
+#[cfg(not(any(
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "freebsd",
+    target_os = "dragonfly",
+    target_os = "openbsd",
+    target_os = "netbsd",
+)))]
+pub const MAX_SOCKET_PATH_LEN: usize = 107;
+
+/// The error returned by [`Builder::make_socket`](crate::Builder::make_socket),
+/// [`Builder::socket_path`](crate::Builder::socket_path), and their `_in`
+/// variants if the socket path would be too long.
+///
+/// Unix domain socket paths are limited to [`MAX_SOCKET_PATH_LEN`] bytes. This
+/// error is wrapped in an [`io::Error`] with kind
+/// [`io::ErrorKind::InvalidInput`], and can be retrieved with
+/// [`io::Error::get_ref`] and `downcast_ref`.
+///
+/// # Examples
+///
+/// ```
+/// use camino_tempfile::{Builder, SocketPathTooLongError};
+///
+/// let long_dir = "a/".repeat(64);
+/// let error = Builder::new()
+///     .make_socket_in(&long_dir, |_| Ok(()))
+///     .unwrap_err();
+///
+/// let error = error
+///     .get_ref()
+///     .and_then(|error| error.downcast_ref::<SocketPathTooLongError>())
+///     .expect("error is a SocketPathTooLongError");
+/// assert_eq!(error.dir(), long_dir);
+/// ```
+#[derive(Clone, Debug)]
+pub struct SocketPathTooLongError {
+    dir: Utf8PathBuf,
+    len: usize,
+}
+
+impl SocketPathTooLongError {
+    /// Returns the directory the socket would have been created in.
+    ///
+    /// For [`Builder::make_socket`](crate::Builder::make_socket) and
+    /// [`Builder::socket_path`](crate::Builder::socket_path), this is
+    /// [`std::env::temp_dir()`].
+    pub fn dir(&self) -> &Utf8Path {
+        &self.dir
+    }
+
+    /// Returns the length, in bytes, that the socket path would have had.
+    pub fn path_len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns the maximum length of a socket path, in bytes. This is always
+    /// [`MAX_SOCKET_PATH_LEN`].
+    pub fn max_len(&self) -> usize {
+        MAX_SOCKET_PATH_LEN
+    }
+}
+
+impl fmt::Display for SocketPathTooLongError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Unix socket path in {} would be {} bytes long, \
+             but socket paths are limited to {} bytes on this platform",
+            self.dir, self.len, MAX_SOCKET_PATH_LEN,
+        )
+    }
+}
+
+impl error::Error for SocketPathTooLongError {}
+
+/// Returns an error if a socket path in `dir`, with a file name that is
+/// `name_len` bytes long, would be too long.
+pub(crate) fn check_socket_dir(dir: &Utf8Path, name_len: usize) -> io::Result<()> {
+    let len = socket_path_len(dir, name_len);
+    if len > MAX_SOCKET_PATH_LEN {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            SocketPathTooLongError {
+                dir: dir.to_owned(),
+                len,
+            },
+        ))
+    } else {
+        Ok(())
+    }
+}
+
+/// Picks a directory to create sockets in, in order of preference:
+///
+/// 1. [`std::env::temp_dir()`].
+/// 2. `$XDG_RUNTIME_DIR`, a short per-user directory on most Linux systems.
+/// 3. `/tmp`.
+///
+/// If none of these are short enough, the error for the first one is returned.
+pub(crate) fn socket_dir(name_len: usize) -> io::Result<Utf8PathBuf> {
+    let temp_dir = utf8_env_temp_dir()?;
+    let error = match check_socket_dir(&temp_dir, name_len) {
+        Ok(()) => return Ok(temp_dir),
+        Err(error) => error,
+    };
+
+    let runtime_dir = env::var("XDG_RUNTIME_DIR")
+        .ok()
+        .map(Utf8PathBuf::from)
+        .filter(|dir| dir.is_absolute() && dir.is_dir());
+    let fallbacks = runtime_dir
+        .into_iter()
+        .chain(std::iter::once(Utf8PathBuf::from("/tmp")));
+    for dir in fallbacks {
+        if check_socket_dir(&dir, name_len).is_ok() {
+            return Ok(dir);
+        }
+    }
+
+    Err(error)
+}
+
+fn socket_path_len(dir: &Utf8Path, name_len: usize) -> usize {
+    // Join a placeholder name, so that separators are accounted for exactly
+    // as they will be for the real path.
+    dir.join("x".repeat(name_len)).as_str().len()
+}
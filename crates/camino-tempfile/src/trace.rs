@@ -0,0 +1,194 @@
+// Copyright (c) The camino-tempfile Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+//! Lifecycle events emitted with the `tracing` feature.
+//!
+//! Each function in this module corresponds to one event, and compiles down to
+//! nothing if the `tracing` feature is disabled. Events are emitted with the
+//! `camino_tempfile` target.
+//!
+//! Successful operations and errors returned to the caller are reported at the
+//! `DEBUG` level. Errors that would otherwise be swallowed, such as failures to
+//! clean up in destructors, are reported at the `WARN` level.
+
+#![cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+
+use camino::Utf8Path;
+use std::io;
+
+/// The kind of temporary entry an event is about.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum EntryKind {
+    File,
+    Dir,
+}
+
+impl EntryKind {
+    #[cfg(feature = "tracing")]
+    fn as_str(self) -> &'static str {
+        match self {
+            EntryKind::File => "file",
+            EntryKind::Dir => "dir",
+        }
+    }
+}
+
+pub(crate) fn create<T>(
+    kind: EntryKind,
+    dir: &Utf8Path,
+    result: &io::Result<T>,
+    path: impl FnOnce(&T) -> &Utf8Path,
+) {
+    #[cfg(feature = "tracing")]
+    match result {
+        Ok(entry) => tracing::debug!(
+            target: "camino_tempfile",
+            kind = kind.as_str(),
+            path = %path(entry),
+            "created temporary {}",
+            kind.as_str(),
+        ),
+        Err(error) => tracing::debug!(
+            target: "camino_tempfile",
+            kind = kind.as_str(),
+            %dir,
+            %error,
+            "failed to create temporary {}",
+            kind.as_str(),
+        ),
+    }
+}
+
+pub(crate) fn persist(
+    from: &Utf8Path,
+    to: &std::path::Path,
+    noclobber: bool,
+    error: Option<&io::Error>,
+) {
+    #[cfg(feature = "tracing")]
+    match error {
+        None => tracing::debug!(
+            target: "camino_tempfile",
+            kind = "file",
+            path = %from,
+            to = %to.display(),
+            noclobber,
+            "persisted temporary file",
+        ),
+        Some(error) => tracing::debug!(
+            target: "camino_tempfile",
+            kind = "file",
+            path = %from,
+            to = %to.display(),
+            noclobber,
+            %error,
+            "failed to persist temporary file",
+        ),
+    }
+}
+
+pub(crate) fn keep(kind: EntryKind, path: &Utf8Path, error: Option<&io::Error>) {
+    #[cfg(feature = "tracing")]
+    match error {
+        None => tracing::debug!(
+            target: "camino_tempfile",
+            kind = kind.as_str(),
+            %path,
+            "kept temporary {}",
+            kind.as_str(),
+        ),
+        Some(error) => tracing::debug!(
+            target: "camino_tempfile",
+            kind = kind.as_str(),
+            %path,
+            %error,
+            "failed to keep temporary {}",
+            kind.as_str(),
+        ),
+    }
+}
+
+pub(crate) fn close(kind: EntryKind, path: &Utf8Path, result: &io::Result<()>) {
+    #[cfg(feature = "tracing")]
+    match result {
+        Ok(()) => tracing::debug!(
+            target: "camino_tempfile",
+            kind = kind.as_str(),
+            %path,
+            "closed and removed temporary {}",
+            kind.as_str(),
+        ),
+        Err(error) => tracing::debug!(
+            target: "camino_tempfile",
+            kind = kind.as_str(),
+            %path,
+            %error,
+            "failed to remove temporary {} on close",
+            kind.as_str(),
+        ),
+    }
+}
+
+pub(crate) fn drop_cleanup(kind: EntryKind, path: &Utf8Path, result: &io::Result<()>) {
+    #[cfg(feature = "tracing")]
+    match result {
+        Ok(()) => tracing::debug!(
+            target: "camino_tempfile",
+            kind = kind.as_str(),
+            %path,
+            "removed temporary {} on drop",
+            kind.as_str(),
+        ),
+        Err(error) => tracing::warn!(
+            target: "camino_tempfile",
+            kind = kind.as_str(),
+            %path,
+            %error,
+            "failed to remove temporary {} on drop",
+            kind.as_str(),
+        ),
+    }
+}
+
+pub(crate) fn background_queued(
+    path: &Utf8Path,
+    trash_path: &Utf8Path,
+    rename_error: Option<&io::Error>,
+) {
+    #[cfg(feature = "tracing")]
+    match rename_error {
+        None => tracing::debug!(
+            target: "camino_tempfile",
+            kind = "dir",
+            %path,
+            %trash_path,
+            "queued temporary dir for background removal",
+        ),
+        Some(error) => tracing::warn!(
+            target: "camino_tempfile",
+            kind = "dir",
+            %path,
+            %error,
+            "failed to rename temporary dir before background removal, removing in place",
+        ),
+    }
+}
+
+pub(crate) fn background_cleanup(trash_path: &Utf8Path, result: &io::Result<()>) {
+    #[cfg(feature = "tracing")]
+    match result {
+        Ok(()) => tracing::debug!(
+            target: "camino_tempfile",
+            kind = "dir",
+            path = %trash_path,
+            "removed temporary dir in background",
+        ),
+        Err(error) => tracing::warn!(
+            target: "camino_tempfile",
+            kind = "dir",
+            path = %trash_path,
+            %error,
+            "failed to remove temporary dir in background",
+        ),
+    }
+}
@@ -0,0 +1,80 @@
+// Copyright (c) The camino-tempfile Contributors
+// SPDX-License-Identifier: MIT OR Apache-2.0
+
+use crate::errors::IoResultExt;
+use camino::Utf8Path;
+use rustix::{
+    fs::{XattrFlags, fsetxattr, lgetxattr, llistxattr, lsetxattr},
+    io::Errno,
+};
+use std::{fs::File, io};
+
+/// Sets the extended attribute `name` on `path`, without following symlinks.
+pub(crate) fn set(path: &Utf8Path, name: &str, value: &[u8]) -> io::Result<()> {
+    lsetxattr(path.as_std_path(), name, value, XattrFlags::empty())
+        .map_err(to_io_error)
+        .with_err_path(|| path)
+}
+
+/// Sets the extended attribute `name` on the open file `file`.
+pub(crate) fn fset(file: &File, name: &str, value: &[u8]) -> io::Result<()> {
+    fsetxattr(file, name, value, XattrFlags::empty()).map_err(to_io_error)
+}
+
+/// Lists the extended attributes of `path` along with their values, without
+/// following symlinks.
+pub(crate) fn list(path: &Utf8Path) -> io::Result<Vec<(String, Vec<u8>)>> {
+    let names = read_to_vec(|buf| llistxattr(path.as_std_path(), buf)).with_err_path(|| path)?;
+
+    // The list is a sequence of NUL-terminated names.
+    let mut xattrs = Vec::new();
+    for name in names.split(|&b| b == 0).filter(|name| !name.is_empty()) {
+        let name = String::from_utf8(name.to_vec())
+            .map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "extended attribute name is not valid UTF-8",
+                )
+            })
+            .with_err_path(|| path)?;
+        let value = match read_to_vec(|buf| lgetxattr(path.as_std_path(), name.as_str(), buf)) {
+            Ok(value) => value,
+            // The attribute was removed after the list was read.
+            Err(error) if error.raw_os_error() == Some(Errno::NODATA.raw_os_error()) => continue,
+            Err(error) => return Err(error).with_err_path(|| path),
+        };
+        xattrs.push((name, value));
+    }
+    Ok(xattrs)
+}
+
+/// Calls `f` once to find out how large a buffer is needed, and then again to
+/// fill it in, retrying if the data grew in between.
+fn read_to_vec<F>(mut f: F) -> io::Result<Vec<u8>>
+where
+    F: FnMut(&mut [u8]) -> rustix::io::Result<usize>,
+{
+    loop {
+        let len = f(&mut []).map_err(to_io_error)?;
+        let mut buf = vec![0; len];
+        match f(&mut buf) {
+            Ok(len) => {
+                buf.truncate(len);
+                return Ok(buf);
+            }
+            Err(Errno::RANGE) => continue,
+            Err(error) => return Err(to_io_error(error)),
+        }
+    }
+}
+
+/// Converts `error` into an [`io::Error`], mapping `EOPNOTSUPP` (returned by
+/// filesystems that don't support user extended attributes) to
+/// [`io::ErrorKind::Unsupported`].
+fn to_io_error(error: Errno) -> io::Error {
+    if error == Errno::OPNOTSUPP {
+        io::Error::new(io::ErrorKind::Unsupported, io::Error::from(error))
+    } else {
+        error.into()
+    }
+}
@@ -0,0 +1,77 @@
+#![deny(rust_2018_idioms)]
+#![cfg(unix)]
+
+use camino_tempfile::{Builder, tempdir};
+use std::{
+    fs,
+    io::{Read, Write},
+    os::unix::fs::{FileTypeExt, PermissionsExt},
+    thread,
+};
+
+#[test]
+fn test_basic() {
+    let tmpdir = tempdir().unwrap();
+    let fifo = Builder::new()
+        .prefix("prefix")
+        .suffix(".fifo")
+        .fifo_in(tmpdir.path())
+        .unwrap();
+
+    let name = fifo.path().file_name().unwrap();
+    assert!(name.starts_with("prefix"), "name: {name}");
+    assert!(name.ends_with(".fifo"), "name: {name}");
+    assert!(fs::metadata(fifo.path()).unwrap().file_type().is_fifo());
+
+    let path = fifo.path().to_owned();
+    drop(fifo);
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_permissions() {
+    let fifo = Builder::new()
+        .permissions(fs::Permissions::from_mode(0o600))
+        .fifo()
+        .unwrap();
+    let mode = fs::metadata(fifo.path()).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+    fifo.close().unwrap();
+}
+
+#[test]
+fn test_open_pair() {
+    let fifo = Builder::new().fifo().unwrap();
+    let (mut reader, mut writer) = fifo.open_pair().unwrap();
+    writer.write_all(b"abcde").unwrap();
+    drop(writer);
+
+    let mut buf = String::new();
+    reader.read_to_string(&mut buf).unwrap();
+    assert_eq!(buf, "abcde");
+}
+
+#[test]
+fn test_open_read_then_write() {
+    let fifo = Builder::new().fifo().unwrap();
+    let mut reader = fifo.open_read().unwrap();
+
+    let path = fifo.path().to_owned();
+    let writer = thread::spawn(move || {
+        let mut writer = fs::OpenOptions::new().write(true).open(path).unwrap();
+        writer.write_all(b"from another thread").unwrap();
+    });
+    writer.join().unwrap();
+
+    let mut buf = String::new();
+    reader.read_to_string(&mut buf).unwrap();
+    assert_eq!(buf, "from another thread");
+}
+
+#[test]
+fn test_keep() {
+    let fifo = Builder::new().fifo().unwrap();
+    let path = fifo.keep().unwrap();
+    assert!(fs::metadata(&path).unwrap().file_type().is_fifo());
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,131 @@
+#![deny(rust_2018_idioms)]
+
+use camino_tempfile::{
+    ContentHasher, Digest, DigestMismatchError, HashingWriter, NamedUtf8TempFile, Utf8TempDir,
+};
+use std::{
+    fs,
+    io::{self, Write},
+};
+
+/// A simple Fletcher-16 checksum, so that these tests don't depend on any
+/// features.
+#[derive(Debug, Default)]
+struct Fletcher16 {
+    a: u8,
+    b: u8,
+}
+
+impl ContentHasher for Fletcher16 {
+    fn update(&mut self, data: &[u8]) {
+        for byte in data {
+            self.a = ((u16::from(self.a) + u16::from(*byte)) % 255) as u8;
+            self.b = ((u16::from(self.b) + u16::from(self.a)) % 255) as u8;
+        }
+    }
+
+    fn digest(&self) -> Digest {
+        Digest::from_bytes(&[self.b, self.a])
+    }
+}
+
+#[test]
+fn test_digest_parse() {
+    let digest: Digest = "00aBcD".parse().unwrap();
+    assert_eq!(digest.as_bytes(), [0x00, 0xab, 0xcd]);
+    assert_eq!(digest.to_string(), "00abcd");
+    assert_eq!(format!("{digest:?}"), "Digest(00abcd)");
+
+    "abc".parse::<Digest>().unwrap_err();
+    "zz".parse::<Digest>().unwrap_err();
+    "é0".parse::<Digest>().unwrap_err();
+}
+
+#[test]
+fn test_hashing_writer() {
+    let mut writer = HashingWriter::new(Vec::new(), Fletcher16::default());
+    writer.write_all(b"abcde").unwrap();
+    assert_eq!(writer.bytes_written(), 5);
+    assert_eq!(writer.digest().to_string(), "c8f0");
+
+    // Data written directly to the inner writer isn't hashed.
+    writer.get_mut().extend_from_slice(b"f");
+    assert_eq!(writer.digest().to_string(), "c8f0");
+    assert_eq!(writer.into_inner(), b"abcdef");
+}
+
+#[test]
+fn test_persist_if_digest() {
+    let dir = Utf8TempDir::new().unwrap();
+    let target = dir.path().join("target");
+
+    let mut file = NamedUtf8TempFile::new_in(dir.path())
+        .unwrap()
+        .into_hashing(Fletcher16::default());
+    file.write_all(b"abcde").unwrap();
+
+    let expected = "c8f0".parse().unwrap();
+    let writer = file.persist_if_digest(&expected, &target).unwrap();
+    assert_eq!(writer.digest(), expected);
+    assert_eq!(fs::read(&target).unwrap(), b"abcde");
+}
+
+#[test]
+fn test_persist_if_digest_mismatch() {
+    let dir = Utf8TempDir::new().unwrap();
+    let target = dir.path().join("target");
+
+    let mut file = NamedUtf8TempFile::new_in(dir.path())
+        .unwrap()
+        .into_hashing(Fletcher16::default());
+    file.write_all(b"abcdf").unwrap();
+
+    let expected: Digest = "c8f0".parse().unwrap();
+    let error = file.persist_if_digest(&expected, &target).unwrap_err();
+    assert_eq!(error.error.kind(), io::ErrorKind::InvalidData);
+    let mismatch = error
+        .error
+        .get_ref()
+        .and_then(|error| error.downcast_ref::<DigestMismatchError>())
+        .expect("error is a DigestMismatchError");
+    assert_eq!(mismatch.expected(), &expected);
+    assert_eq!(mismatch.actual().to_string(), "c9f1");
+    assert!(!target.exists());
+
+    // The file is returned and can still be used.
+    let mut file = error.file;
+    assert!(file.path().exists());
+    file.write_all(b"").unwrap();
+    assert_eq!(file.as_file().digest(), *mismatch.actual());
+}
+
+#[cfg(feature = "sha256")]
+#[test]
+fn test_sha256() {
+    use camino_tempfile::Sha256Hasher;
+
+    let mut file = NamedUtf8TempFile::new()
+        .unwrap()
+        .into_hashing(Sha256Hasher::new());
+    assert_eq!(
+        file.as_file().digest().to_string(),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+    );
+    file.write_all(b"hello").unwrap();
+    assert_eq!(
+        file.as_file().digest().to_string(),
+        "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824",
+    );
+}
+
+#[cfg(feature = "xxh3")]
+#[test]
+fn test_xxh3() {
+    use camino_tempfile::Xxh3Hasher;
+
+    let mut hasher = Xxh3Hasher::new();
+    assert_eq!(hasher.digest().to_string(), "2d06800538d394c2");
+    hasher.update(b"hello");
+    assert_eq!(hasher.digest().as_bytes().len(), 8);
+    assert_ne!(hasher.digest().to_string(), "2d06800538d394c2");
+}
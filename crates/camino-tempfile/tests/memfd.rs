@@ -0,0 +1,73 @@
+#![deny(rust_2018_idioms)]
+#![cfg(all(feature = "memfd", target_os = "linux"))]
+
+use camino_tempfile::{MemfdSeals, MemfdUtf8TempFile};
+use std::{
+    fs,
+    io::{Read, Seek, SeekFrom, Write},
+};
+
+#[test]
+fn test_basic() {
+    let mut file = MemfdUtf8TempFile::new().unwrap();
+    write!(file, "abcde").unwrap();
+    file.seek(SeekFrom::Start(0)).unwrap();
+    let mut buf = String::new();
+    file.read_to_string(&mut buf).unwrap();
+    assert_eq!("abcde", buf);
+}
+
+#[test]
+fn test_path() {
+    let mut file = MemfdUtf8TempFile::with_name("test-path").unwrap();
+    write!(file, "abcde").unwrap();
+
+    let path = file.path().to_owned();
+    assert!(path.starts_with(format!("/proc/{}/fd", std::process::id())));
+    let target = fs::read_link(&path).unwrap();
+    assert!(
+        target.to_str().unwrap().starts_with("/memfd:test-path"),
+        "target: {}",
+        target.display()
+    );
+    assert_eq!(fs::read_to_string(&path).unwrap(), "abcde");
+
+    let mut reopened = file.reopen().unwrap();
+    let mut buf = String::new();
+    reopened.read_to_string(&mut buf).unwrap();
+    assert_eq!("abcde", buf);
+
+    drop(reopened);
+    drop(file);
+    assert!(fs::read_to_string(&path).is_err());
+}
+
+#[test]
+fn test_seals() {
+    let mut file = MemfdUtf8TempFile::new().unwrap();
+    assert_eq!(file.seals().unwrap(), MemfdSeals::empty());
+    write!(file, "abcde").unwrap();
+
+    file.add_seals(MemfdSeals::SHRINK | MemfdSeals::GROW)
+        .unwrap();
+    let seals = file.seals().unwrap();
+    assert!(seals.contains(MemfdSeals::SHRINK | MemfdSeals::GROW));
+    assert!(!seals.contains(MemfdSeals::WRITE));
+
+    // Overwriting is allowed, but growing and shrinking isn't.
+    file.seek(SeekFrom::Start(0)).unwrap();
+    write!(file, "ABCDE").unwrap();
+    write!(file, "f").unwrap_err();
+    file.as_file().set_len(1).unwrap_err();
+
+    file.add_seals(MemfdSeals::WRITE | MemfdSeals::SEAL)
+        .unwrap();
+    file.seek(SeekFrom::Start(0)).unwrap();
+    write!(file, "a").unwrap_err();
+    file.add_seals(MemfdSeals::FUTURE_WRITE).unwrap_err();
+
+    file.seek(SeekFrom::Start(0)).unwrap();
+    let mut buf = String::new();
+    file.read_to_string(&mut buf).unwrap();
+    assert_eq!("ABCDE", buf);
+}
@@ -412,3 +412,380 @@ fn test_overly_generic_bounds() {
         };
     }
 }
+
+#[test]
+fn test_sibling_of() {
+    let tmp_dir = tempdir().unwrap();
+    let target = tmp_dir.path().join("target.txt");
+
+    let mut tmpfile = NamedUtf8TempFile::new_sibling_of(&target).unwrap();
+    assert_eq!(tmpfile.path().parent(), Some(tmp_dir.path()));
+    let name = tmpfile.path().file_name().unwrap();
+    assert!(name.starts_with(".target.txt."), "name: {name}");
+    assert!(name.ends_with(".txt"), "name: {name}");
+    assert!(!target.exists());
+
+    write!(tmpfile, "abcde").unwrap();
+    tmpfile.persist(&target).unwrap();
+    assert_eq!(std::fs::read_to_string(&target).unwrap(), "abcde");
+}
+
+#[test]
+fn test_sibling_of_custom() {
+    let tmp_dir = tempdir().unwrap();
+    let target = tmp_dir.path().join("target");
+
+    // No extension: there's no default suffix.
+    let tmpfile = Builder::new()
+        .rand_bytes(4)
+        .tempfile_sibling_of(&target)
+        .unwrap();
+    let name = tmpfile.path().file_name().unwrap();
+    assert!(name.starts_with(".target."), "name: {name}");
+    assert_eq!(name.len(), ".target.".len() + 4);
+
+    // An explicit prefix and suffix override the defaults.
+    let tmpfile = Builder::new()
+        .prefix("prefix")
+        .suffix(".suffix")
+        .tempfile_sibling_of(&target)
+        .unwrap();
+    assert_eq!(tmpfile.path().parent(), Some(tmp_dir.path()));
+    let name = tmpfile.path().file_name().unwrap();
+    assert!(name.starts_with("prefix"), "name: {name}");
+    assert!(name.ends_with(".suffix"), "name: {name}");
+}
+
+#[test]
+fn test_sibling_of_no_file_name() {
+    let error = NamedUtf8TempFile::new_sibling_of("/").unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_make_socket() {
+    use camino_tempfile::MAX_SOCKET_PATH_LEN;
+    use std::os::unix::net::{UnixListener, UnixStream};
+
+    let tmpsock = Builder::new()
+        .prefix("tmpunix")
+        .make_socket(|path| UnixListener::bind(path))
+        .unwrap();
+    assert!(tmpsock.path().as_str().len() <= MAX_SOCKET_PATH_LEN);
+    assert!(tmpsock.path().exists());
+    UnixStream::connect(tmpsock.path()).unwrap();
+}
+
+#[cfg(unix)]
+#[test]
+fn test_make_socket_in_too_long() {
+    use camino_tempfile::{MAX_SOCKET_PATH_LEN, SocketPathTooLongError};
+    use std::io;
+
+    let tmpdir = tempdir().unwrap();
+    let long_dir = tmpdir.path().join("a".repeat(MAX_SOCKET_PATH_LEN));
+
+    let mut called = false;
+    let error = Builder::new()
+        .make_socket_in(&long_dir, |_| {
+            called = true;
+            Ok(())
+        })
+        .unwrap_err();
+    assert!(!called, "closure should not be called");
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+
+    let error = error
+        .get_ref()
+        .and_then(|error| error.downcast_ref::<SocketPathTooLongError>())
+        .expect("error is a SocketPathTooLongError");
+    assert_eq!(error.dir(), long_dir);
+    assert!(error.path_len() > error.max_len());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_socket_path() {
+    use camino_tempfile::{MAX_SOCKET_PATH_LEN, SocketPathTooLongError};
+    use std::{
+        io,
+        os::unix::net::{UnixListener, UnixStream},
+    };
+
+    let path = Builder::new().prefix("tmpunix").socket_path().unwrap();
+    assert!(path.as_str().len() <= MAX_SOCKET_PATH_LEN);
+    assert!(path.file_name().unwrap().starts_with("tmpunix"));
+    assert!(!path.exists());
+
+    let _listener = UnixListener::bind(&path).unwrap();
+    UnixStream::connect(&path).unwrap();
+    let path_buf = path.to_path_buf();
+    path.close().unwrap();
+    assert!(!path_buf.exists());
+
+    let tmpdir = tempdir().unwrap();
+    let long_dir = tmpdir.path().join("a".repeat(MAX_SOCKET_PATH_LEN));
+    let error = Builder::new().socket_path_in(&long_dir).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+    let error = error
+        .get_ref()
+        .and_then(|error| error.downcast_ref::<SocketPathTooLongError>())
+        .expect("error is a SocketPathTooLongError");
+    assert_eq!(error.dir(), long_dir);
+}
+
+#[test]
+fn test_into_inner_try_from() {
+    let mut tmpfile = NamedUtf8TempFile::new().unwrap();
+    write!(tmpfile, "abcde").unwrap();
+    let path = tmpfile.path().to_owned();
+
+    let inner = tmpfile.into_inner();
+    assert_eq!(inner.path(), path);
+    let tmpfile = NamedUtf8TempFile::try_from(inner).unwrap();
+    assert_eq!(tmpfile.path(), path);
+
+    let temp_path = tmpfile.into_temp_path().into_inner();
+    assert_eq!(&*temp_path, path);
+    let temp_path = Utf8TempPath::try_from(temp_path).unwrap();
+    assert_eq!(std::fs::read_to_string(&temp_path).unwrap(), "abcde");
+
+    drop(temp_path);
+    assert!(!exists(&path));
+
+    // A `NamedTempFile` or `TempPath` with cleanup disabled is still kept after converting.
+    let mut inner = tempfile::NamedTempFile::new().unwrap();
+    inner.disable_cleanup(true);
+    let path = inner.path().to_path_buf();
+    drop(NamedUtf8TempFile::try_from(inner).unwrap());
+    assert!(exists(&path));
+
+    let mut temp_path = tempfile::TempPath::from_path(&path);
+    temp_path.disable_cleanup(true);
+    drop(Utf8TempPath::try_from(temp_path).unwrap());
+    assert!(exists(&path));
+
+    drop(Utf8TempPath::try_from(tempfile::TempPath::from_path(&path)).unwrap());
+    assert!(!exists(&path));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_try_from_non_utf8() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    let inner = tempfile::Builder::new()
+        .prefix(OsStr::from_bytes(b"non-utf8-\xff"))
+        .tempfile()
+        .unwrap();
+    let path = inner.path().to_path_buf();
+
+    let error = NamedUtf8TempFile::try_from(inner).unwrap_err();
+    assert_eq!(error.path(), path);
+    let inner = error.into_inner();
+
+    let error = Utf8TempPath::try_from(inner.into_temp_path()).unwrap_err();
+    assert_eq!(error.path(), path);
+    error.into_inner().close().unwrap();
+}
+
+#[test]
+fn test_error_inspection() {
+    use camino_tempfile::{Utf8TempError, Utf8TempOperation};
+
+    let tmpdir = tempdir().unwrap();
+    let missing_dir = tmpdir.path().join("missing");
+
+    let error = Builder::new().tempfile_in(&missing_dir).unwrap_err();
+    let error = Utf8TempError::from_io_error(&error).expect("error is a Utf8TempError");
+    assert_eq!(error.operation(), Utf8TempOperation::CreateFile);
+    assert_eq!(error.path(), Some(missing_dir.as_path()));
+    assert!(!error.is_non_utf8());
+    assert_eq!(
+        error.io_error().unwrap().kind(),
+        std::io::ErrorKind::NotFound
+    );
+    // tempfile's error already mentions the path of the temporary file.
+    assert!(
+        error.to_string().ends_with("(creating temporary file)"),
+        "{error}"
+    );
+
+    let target = missing_dir.join("target");
+    let tmpfile = NamedUtf8TempFile::new_in(tmpdir.path()).unwrap();
+    let error = tmpfile.persist(&target).unwrap_err();
+    let inner = Utf8TempError::from_io_error(&error.error).expect("error is a Utf8TempError");
+    assert_eq!(inner.operation(), Utf8TempOperation::Persist);
+    assert_eq!(inner.path(), Some(target.as_path()));
+    assert!(
+        inner
+            .to_string()
+            .ends_with(&format!("(persisting temporary file to {target})")),
+        "{inner}"
+    );
+
+    let tmpfile = error.file;
+    std::fs::remove_file(tmpfile.path()).unwrap();
+    let path = tmpfile.path().to_owned();
+    let error = tmpfile.close().unwrap_err();
+    let error = Utf8TempError::from_io_error(&error).expect("error is a Utf8TempError");
+    assert_eq!(error.operation(), Utf8TempOperation::Cleanup);
+    assert_eq!(error.path(), Some(path.as_path()));
+    // The path is only mentioned once in the message.
+    assert_eq!(error.to_string().matches(path.as_str()).count(), 1);
+}
+
+#[test]
+fn test_copy_of() {
+    let tmpdir = tempdir().unwrap();
+    let src = tmpdir.path().join("src.txt");
+    std::fs::write(&src, "abcde").unwrap();
+
+    let (mut tmpfile, _strategy) = NamedUtf8TempFile::copy_of(&src).unwrap();
+    let mut buf = String::new();
+    tmpfile.read_to_string(&mut buf).unwrap();
+    assert_eq!(buf, "abcde");
+
+    write!(tmpfile, "fghij").unwrap();
+    assert_eq!(std::fs::read_to_string(&src).unwrap(), "abcde");
+    assert_eq!(
+        std::fs::read_to_string(tmpfile.path()).unwrap(),
+        "abcdefghij"
+    );
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_xattr() {
+    let tmpdir = tempdir().unwrap();
+    let result = Builder::new()
+        .xattr("user.camino-tempfile.a", "1")
+        .xattr("user.camino-tempfile.b", [0, 255])
+        .xattr("user.camino-tempfile.a", "2")
+        .tempfile_in(tmpdir.path());
+    let tmpfile = match result {
+        Ok(tmpfile) => tmpfile,
+        Err(error) if error.kind() == std::io::ErrorKind::Unsupported => {
+            eprintln!("skipping test: user extended attributes are not supported");
+            return;
+        }
+        Err(error) => panic!("failed to create temporary file: {error}"),
+    };
+
+    tmpfile.set_xattr("user.camino-tempfile.c", "3").unwrap();
+    let mut xattrs = tmpfile.xattrs().unwrap();
+    xattrs.retain(|(name, _)| name.starts_with("user.camino-tempfile."));
+    xattrs.sort();
+    assert_eq!(
+        xattrs,
+        [
+            ("user.camino-tempfile.a".to_owned(), b"2".to_vec()),
+            ("user.camino-tempfile.b".to_owned(), vec![0, 255]),
+            ("user.camino-tempfile.c".to_owned(), b"3".to_vec()),
+        ]
+    );
+
+    drop(tmpfile);
+
+    // Attributes are set before the mode is changed, so they can be set even if
+    // the mode doesn't allow writing.
+    let tmpfile = Builder::new()
+        .exact_permissions(0o444)
+        .xattr("user.camino-tempfile.a", "1")
+        .tempfile_in(tmpdir.path())
+        .unwrap();
+    assert!(
+        tmpfile
+            .xattrs()
+            .unwrap()
+            .contains(&("user.camino-tempfile.a".to_owned(), b"1".to_vec()))
+    );
+    drop(tmpfile);
+    Builder::new()
+        .exact_permissions(0o555)
+        .xattr("user.camino-tempfile.a", "1")
+        .tempdir_in(tmpdir.path())
+        .unwrap()
+        .close()
+        .unwrap();
+
+    // Only privileged processes can set attributes in the `trusted.` namespace.
+    // Entries whose attributes can't be set are removed.
+    let result = Builder::new()
+        .xattr("trusted.camino-tempfile", "1")
+        .tempfile_in(tmpdir.path());
+    match result {
+        Ok(tmpfile) => assert!(
+            tmpfile
+                .xattrs()
+                .unwrap()
+                .contains(&("trusted.camino-tempfile".to_owned(), b"1".to_vec()))
+        ),
+        Err(error) => {
+            assert_eq!(error.kind(), std::io::ErrorKind::PermissionDenied);
+            assert_eq!(std::fs::read_dir(tmpdir.path()).unwrap().count(), 0);
+        }
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_exact_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    fn mode(path: &Utf8Path) -> u32 {
+        std::fs::symlink_metadata(path)
+            .unwrap()
+            .permissions()
+            .mode()
+            & 0o7777
+    }
+
+    let tmpdir = tempdir().unwrap();
+
+    // Group- and world-writable modes are normally masked out by the umask.
+    let tmpfile = Builder::new()
+        .permissions(std::fs::Permissions::from_mode(0o600))
+        .exact_permissions(0o666)
+        .tempfile_in(tmpdir.path())
+        .unwrap();
+    assert_eq!(mode(tmpfile.path()), 0o666);
+
+    let tmpfile = Builder::new()
+        .exact_permissions(0o000)
+        .tempfile_in(tmpdir.path())
+        .unwrap();
+    assert_eq!(mode(tmpfile.path()), 0o000);
+
+    let made = Builder::new()
+        .exact_permissions(0o622)
+        .make_in(tmpdir.path(), |path| std::fs::File::create(path))
+        .unwrap();
+    assert_eq!(mode(made.path()), 0o622);
+
+    let fifo = Builder::new()
+        .exact_permissions(0o622)
+        .fifo_in(tmpdir.path())
+        .unwrap();
+    assert_eq!(mode(fifo.path()), 0o622);
+
+    // Symbolic links, and their targets, are left alone.
+    let target = tmpdir.path().join("target");
+    std::fs::write(&target, "abcde").unwrap();
+    std::fs::set_permissions(&target, std::fs::Permissions::from_mode(0o600)).unwrap();
+    let link = Builder::new()
+        .exact_permissions(0o666)
+        .make_in(tmpdir.path(), |path| {
+            std::os::unix::fs::symlink(&target, path)
+        })
+        .unwrap();
+    assert!(link.path().is_symlink());
+    assert_eq!(mode(&target), 0o600);
+
+    // Closures that don't create anything are fine too.
+    Builder::new()
+        .exact_permissions(0o666)
+        .make_in(tmpdir.path(), |_| Ok(()))
+        .unwrap();
+}
@@ -0,0 +1,23 @@
+#![deny(rust_2018_idioms)]
+// This test modifies the environment, so it's in its own test binary to avoid
+// interfering with other tests.
+#![cfg(unix)]
+
+use camino_tempfile::{Utf8TempError, Utf8TempOperation, tempdir};
+use std::{env, ffi::OsStr, io, os::unix::ffi::OsStrExt, path::Path};
+
+#[test]
+fn test_non_utf8_temp_dir() {
+    let temp_dir = OsStr::from_bytes(b"/tmp/non-utf8-\xff");
+    env::set_var("TMPDIR", temp_dir);
+
+    let error = tempdir().unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+
+    let error = Utf8TempError::from_io_error(&error).expect("error is a Utf8TempError");
+    assert_eq!(error.operation(), Utf8TempOperation::ValidateUtf8);
+    assert!(error.is_non_utf8());
+    assert!(error.io_error().is_none());
+    assert_eq!(error.path(), None);
+    assert_eq!(error.std_path(), Path::new(temp_dir));
+}
@@ -0,0 +1,141 @@
+#![deny(rust_2018_idioms)]
+#![cfg(unix)]
+
+use camino_tempfile::{Builder, JoinedUtf8TempDir, PROCESS_LOCK_FILE_NAME, Utf8TempDir};
+use std::{
+    env, fs,
+    io::{self, BufRead, BufReader, Read},
+    process::{Command, Stdio},
+};
+
+// Each `flock` is tied to an open file description, so handles opened within a
+// single process behave like handles in separate processes.
+
+#[test]
+fn test_creator_dropped_first() {
+    let dir = Builder::new().process_shared(true).tempdir().unwrap();
+    let path = dir.path().to_owned();
+    assert!(path.join(PROCESS_LOCK_FILE_NAME).is_file());
+
+    let joined = JoinedUtf8TempDir::join(&path).unwrap();
+    assert_eq!(joined.path(), path);
+
+    drop(dir);
+    assert!(path.exists());
+    drop(joined);
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_joiner_dropped_first() {
+    let dir = Builder::new().process_shared(true).tempdir().unwrap();
+    let path = dir.path().to_owned();
+
+    let first = JoinedUtf8TempDir::join(&path).unwrap();
+    let second = JoinedUtf8TempDir::join(&path).unwrap();
+
+    drop(first);
+    assert!(path.exists());
+    second.close().unwrap();
+    assert!(path.exists());
+
+    dir.close().unwrap();
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_close_while_in_use() {
+    let dir = Builder::new().process_shared(true).tempdir().unwrap();
+    let path = dir.path().to_owned();
+    let joined = JoinedUtf8TempDir::join(&path).unwrap();
+
+    dir.close().unwrap();
+    assert!(path.exists());
+    joined.close().unwrap();
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_close_after_removed() {
+    // Simulates another process removing the directory between this process
+    // unlocking and taking the exclusive lock.
+    let dir = Builder::new().process_shared(true).tempdir().unwrap();
+    let path = dir.path().to_owned();
+    let joined = JoinedUtf8TempDir::join(&path).unwrap();
+
+    fs::remove_dir_all(&path).unwrap();
+    joined.close().unwrap();
+    dir.close().unwrap();
+}
+
+#[test]
+fn test_join_missing() {
+    let dir = Utf8TempDir::new().unwrap();
+
+    // Not created with `process_shared`.
+    let err = JoinedUtf8TempDir::join(dir.path()).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+    // Already removed by the last user.
+    let shared = Builder::new().process_shared(true).tempdir().unwrap();
+    let path = shared.path().to_owned();
+    drop(shared);
+    let err = JoinedUtf8TempDir::join(&path).unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+}
+
+#[test]
+fn test_keep() {
+    let dir = Builder::new().process_shared(true).tempdir().unwrap();
+    let path = dir.keep();
+    assert!(path.exists());
+
+    // The last process to release its lock still removes the directory.
+    let joined = JoinedUtf8TempDir::join(&path).unwrap();
+    drop(joined);
+    assert!(!path.exists());
+}
+
+const CHILD_ENV: &str = "CAMINO_TEMPFILE_TEST_JOIN_DIR";
+
+#[test]
+fn test_child_process() {
+    let dir = Builder::new().process_shared(true).tempdir().unwrap();
+    let path = dir.path().to_owned();
+
+    let mut child = Command::new(env::current_exe().unwrap())
+        .args(["--exact", "child_process_helper", "--nocapture"])
+        .env(CHILD_ENV, &path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    // Wait for the child to join the directory. libtest may print other output
+    // on the same line. `stdout` is kept open until the child exits, so that
+    // its remaining output can be written.
+    let mut stdout = BufReader::new(child.stdout.take().unwrap()).lines();
+    let joined = stdout.any(|line| line.unwrap().contains("joined"));
+    assert!(joined, "child process joined the directory");
+
+    drop(dir);
+    assert!(path.join("child.txt").exists());
+
+    // Closing stdin tells the child to release its lock.
+    drop(child.stdin.take());
+    assert!(child.wait().unwrap().success());
+    assert!(!path.exists());
+}
+
+#[test]
+fn child_process_helper() {
+    let Ok(path) = env::var(CHILD_ENV) else {
+        return;
+    };
+    let joined = JoinedUtf8TempDir::join(&path).unwrap();
+    fs::write(joined.path().join("child.txt"), "hello").unwrap();
+    println!("joined");
+
+    io::stdin().read_to_end(&mut Vec::new()).unwrap();
+    joined.close().unwrap();
+}
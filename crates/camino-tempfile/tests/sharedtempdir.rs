@@ -0,0 +1,59 @@
+#![deny(rust_2018_idioms)]
+
+use camino_tempfile::{SharedUtf8TempDir, Utf8TempDir};
+use std::{fs, thread};
+
+#[test]
+fn test_deleted_on_last_drop() {
+    let dir = SharedUtf8TempDir::new().unwrap();
+    let path = dir.path().to_owned();
+
+    let clone = dir.clone();
+    assert_eq!(dir.handle_count(), 2);
+    assert_eq!(clone.path(), path);
+
+    drop(dir);
+    assert!(path.exists());
+    assert_eq!(clone.handle_count(), 1);
+
+    drop(clone);
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_threads() {
+    let dir = Utf8TempDir::new().unwrap().into_shared();
+
+    let workers: Vec<_> = (0..4)
+        .map(|i| {
+            let dir = dir.clone();
+            thread::spawn(move || fs::write(dir.path().join(format!("{i}.txt")), "done").unwrap())
+        })
+        .collect();
+    for worker in workers {
+        worker.join().unwrap();
+    }
+
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 4);
+    let path = dir.path().to_owned();
+    dir.try_close().expect("last owner").unwrap();
+    assert!(!path.exists());
+}
+
+#[test]
+fn test_try_close_shared() {
+    let dir = SharedUtf8TempDir::new().unwrap();
+    let path = dir.path().to_owned();
+    let clone = dir.clone();
+
+    // Closing fails while another handle exists, and the handle is returned.
+    let dir = dir.try_close().expect_err("other handle exists");
+    assert!(path.exists());
+    assert_eq!(dir.handle_count(), 2);
+
+    drop(clone);
+    let dir = dir.try_unwrap().expect("last owner");
+    let kept = dir.keep();
+    assert!(kept.exists());
+    fs::remove_dir(kept).unwrap();
+}
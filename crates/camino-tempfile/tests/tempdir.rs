@@ -11,7 +11,7 @@
 #![deny(rust_2018_idioms)]
 
 use camino::Utf8Path;
-use camino_tempfile::{Builder, Utf8TempDir};
+use camino_tempfile::{Builder, CopyStrategy, Utf8TempDir, wait_for_background_cleanup};
 use std::{env, fs, path::Path, sync::mpsc::channel, thread};
 
 macro_rules! t {
@@ -49,6 +49,178 @@ fn test_customnamed() {
     assert_eq!(name.len(), 24);
 }
 
+#[test]
+fn test_close_in_background() {
+    let parent = t!(Utf8TempDir::new());
+    let tmp = t!(Utf8TempDir::new_in(parent.path()));
+    let path = tmp.path().to_path_buf();
+    t!(fs::create_dir_all(path.join("a/b/c")));
+    t!(fs::write(path.join("a/b/c/file.txt"), "abcde"));
+
+    tmp.close_in_background();
+    // The directory is moved out of the way immediately.
+    assert!(!path.exists());
+
+    wait_for_background_cleanup();
+    assert_eq!(t!(fs::read_dir(parent.path())).count(), 0);
+}
+
+#[test]
+fn test_clone_tree_from() {
+    let src = t!(Utf8TempDir::new());
+    t!(fs::create_dir_all(src.path().join("a/b")));
+    t!(fs::write(src.path().join("top.txt"), "top"));
+    t!(fs::write(src.path().join("a/b/nested.txt"), "nested"));
+    #[cfg(unix)]
+    t!(std::os::unix::fs::symlink(
+        "top.txt",
+        src.path().join("link")
+    ));
+
+    let (dir, report) = t!(Utf8TempDir::clone_tree_from(src.path()));
+    assert_eq!(report.total(), 2);
+    assert_eq!(report.count(CopyStrategy::Hardlink), 0);
+    assert_eq!(
+        t!(fs::read_to_string(dir.path().join("a/b/nested.txt"))),
+        "nested"
+    );
+    #[cfg(unix)]
+    assert_eq!(
+        t!(fs::read_link(dir.path().join("link"))),
+        Path::new("top.txt")
+    );
+
+    // Modifying the copy doesn't modify the original.
+    t!(fs::write(dir.path().join("top.txt"), "modified"));
+    assert_eq!(t!(fs::read_to_string(src.path().join("top.txt"))), "top");
+}
+
+#[test]
+fn test_link_tree_from() {
+    let src = t!(Utf8TempDir::new());
+    t!(fs::create_dir(src.path().join("a")));
+    t!(fs::write(src.path().join("a/file.txt"), "abcde"));
+
+    let (dir, report) = t!(Utf8TempDir::link_tree_from(src.path()));
+    assert_eq!(report.total(), 1);
+    assert_eq!(
+        t!(fs::read_to_string(dir.path().join("a/file.txt"))),
+        "abcde"
+    );
+
+    // The source and the temporary directory are on the same filesystem, so
+    // files are either reflinked or hard-linked.
+    assert_eq!(report.count(CopyStrategy::Copy), 0, "report: {report:?}");
+}
+
+#[test]
+fn test_into_inner_try_from() {
+    let tmp = t!(Utf8TempDir::new());
+    let path = tmp.path().to_path_buf();
+
+    let inner = tmp.into_inner();
+    assert_eq!(inner.path(), path);
+    let tmp = Utf8TempDir::try_from(inner).unwrap();
+    assert_eq!(tmp.path(), path);
+
+    drop(tmp);
+    assert!(!path.exists());
+
+    // A `TempDir` with cleanup disabled is still kept after converting.
+    let mut inner = t!(tempfile::TempDir::new());
+    inner.disable_cleanup(true);
+    let path = inner.path().to_path_buf();
+    drop(Utf8TempDir::try_from(inner).unwrap());
+    assert!(path.exists());
+    t!(std::fs::remove_dir(&path));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_try_from_non_utf8() {
+    use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+    let inner = t!(tempfile::Builder::new()
+        .prefix(OsStr::from_bytes(b"non-utf8-\xff"))
+        .tempdir());
+    let path = inner.path().to_path_buf();
+
+    let error = Utf8TempDir::try_from(inner).unwrap_err();
+    assert_eq!(error.path(), path);
+    let inner = error.into_inner();
+    assert!(inner.path().exists());
+    t!(inner.close());
+}
+
+#[test]
+fn test_background_cleanup_on_drop() {
+    let parent = t!(Utf8TempDir::new());
+    let path = {
+        let tmp = t!(Builder::new()
+            .background_cleanup(true)
+            .tempdir_in(parent.path()));
+        t!(fs::write(tmp.path().join("file.txt"), "abcde"));
+        tmp.path().to_path_buf()
+    };
+    assert!(!path.exists());
+
+    wait_for_background_cleanup();
+    assert_eq!(t!(fs::read_dir(parent.path())).count(), 0);
+
+    // Disabling cleanup takes priority over background cleanup.
+    let path = {
+        let mut tmp = t!(Builder::new()
+            .background_cleanup(true)
+            .tempdir_in(parent.path()));
+        tmp.disable_cleanup(true);
+        tmp.path().to_path_buf()
+    };
+    assert!(path.exists());
+
+    let tmp = t!(Builder::new()
+        .background_cleanup(true)
+        .tempdir_in(parent.path()));
+    let path = tmp.keep();
+    wait_for_background_cleanup();
+    assert!(path.exists());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_exact_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    // Modes without owner read or write access work too, even though the
+    // directory is opened to change its mode.
+    for exact in [0o777, 0o311, 0o200, 0o000] {
+        let tmp = t!(Builder::new().exact_permissions(exact).tempdir());
+        let mode = t!(tmp.path().metadata()).permissions().mode();
+        assert_eq!(mode & 0o7777, exact);
+        t!(tmp.close());
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_close_read_only() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let tmp = t!(Utf8TempDir::new());
+    let path = tmp.path().to_owned();
+    let sub = path.join("sub");
+    t!(fs::create_dir(&sub));
+    t!(fs::write(sub.join("file"), "hello"));
+    t!(fs::set_permissions(&sub, fs::Permissions::from_mode(0o500)));
+    t!(fs::set_permissions(
+        &path,
+        fs::Permissions::from_mode(0o500)
+    ));
+
+    // Write permissions are restored so that the directory can be removed.
+    t!(tmp.close());
+    assert!(!path.exists());
+}
+
 fn test_rm_tempdir() {
     let (tx, rx) = channel();
     let f = move || {
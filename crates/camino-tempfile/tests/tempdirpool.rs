@@ -0,0 +1,76 @@
+#![deny(rust_2018_idioms)]
+
+use camino_tempfile::{Builder, Utf8TempDirPool, tempdir};
+use std::fs;
+
+#[test]
+fn test_lease_reuse() {
+    let parent = tempdir().unwrap();
+    let pool = Utf8TempDirPool::new_in(parent.path());
+    assert_eq!(pool.idle_count(), 0);
+
+    let first_path = {
+        let lease = pool.lease().unwrap();
+        assert_eq!(lease.path().parent(), Some(parent.path()));
+        fs::write(lease.path().join("file.txt"), "abcde").unwrap();
+        fs::create_dir_all(lease.path().join("a/b/c")).unwrap();
+        fs::write(lease.path().join("a/b/c/file.txt"), "abcde").unwrap();
+        lease.path().to_owned()
+    };
+    assert_eq!(pool.idle_count(), 1);
+    assert!(first_path.exists());
+
+    let lease = pool.lease().unwrap();
+    assert_eq!(lease.path(), first_path);
+    assert_eq!(fs::read_dir(lease.path()).unwrap().count(), 0);
+
+    // A second concurrent lease gets a new directory.
+    let lease2 = pool.lease().unwrap();
+    assert_ne!(lease2.path(), lease.path());
+    drop(lease);
+    drop(lease2);
+    assert_eq!(pool.idle_count(), 2);
+
+    drop(pool);
+    assert_eq!(fs::read_dir(parent.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn test_background_cleanup() {
+    let parent = tempdir().unwrap();
+    let mut pool = Builder::new()
+        .prefix("pool-")
+        .tempdir_pool_in(parent.path());
+    pool.background_cleanup(true).unwrap();
+
+    for _ in 0..16 {
+        let lease = pool.lease().unwrap();
+        assert!(lease.path().file_name().unwrap().starts_with("pool-"));
+        fs::write(lease.path().join("file.txt"), "abcde").unwrap();
+    }
+
+    // Turning background cleanup off waits for pending work.
+    pool.background_cleanup(false).unwrap();
+    assert!(pool.idle_count() >= 1);
+    for entry in parent.path().read_dir_utf8().unwrap() {
+        let entry = entry.unwrap();
+        assert_eq!(fs::read_dir(entry.path()).unwrap().count(), 0);
+    }
+
+    pool.close().unwrap();
+    assert_eq!(fs::read_dir(parent.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn test_into_temp_dir() {
+    let parent = tempdir().unwrap();
+    let pool = Utf8TempDirPool::new_in(parent.path());
+
+    let dir = pool.lease().unwrap().into_temp_dir();
+    assert!(dir.path().exists());
+    assert_eq!(pool.idle_count(), 0);
+
+    let path = dir.path().to_owned();
+    dir.close().unwrap();
+    assert!(!path.exists());
+}
@@ -0,0 +1,128 @@
+#![deny(rust_2018_idioms)]
+#![cfg(feature = "tracing")]
+
+use camino_tempfile::{Builder, NamedUtf8TempFile, Utf8TempDir};
+use std::{
+    fmt, fs,
+    sync::{Arc, Mutex},
+};
+use tracing::{
+    Event, Level, Metadata,
+    field::{Field, Visit},
+    span,
+    subscriber::{self, Subscriber},
+};
+
+/// A minimal subscriber that records the level and message of every event.
+#[derive(Clone, Default)]
+struct Recorder {
+    events: Arc<Mutex<Vec<(Level, String)>>>,
+}
+
+impl Recorder {
+    fn events(&self) -> Vec<(Level, String)> {
+        self.events.lock().unwrap().clone()
+    }
+}
+
+struct MessageVisitor(String);
+
+impl Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        if field.name() == "message" {
+            self.0 = format!("{value:?}");
+        }
+    }
+}
+
+impl Subscriber for Recorder {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        metadata.target() == "camino_tempfile"
+    }
+
+    fn new_span(&self, _span: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _span: &span::Id, _values: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &span::Id, _follows: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut visitor = MessageVisitor(String::new());
+        event.record(&mut visitor);
+        self.events
+            .lock()
+            .unwrap()
+            .push((*event.metadata().level(), visitor.0));
+    }
+
+    fn enter(&self, _span: &span::Id) {}
+
+    fn exit(&self, _span: &span::Id) {}
+}
+
+#[test]
+fn test_file_lifecycle() {
+    let recorder = Recorder::default();
+    let dir = Utf8TempDir::new().unwrap();
+    let target = dir.path().join("persisted");
+
+    subscriber::with_default(recorder.clone(), || {
+        let file = NamedUtf8TempFile::new_in(dir.path()).unwrap();
+        file.persist(&target).unwrap();
+
+        let file = NamedUtf8TempFile::new_in(dir.path()).unwrap();
+        drop(file);
+    });
+
+    let messages: Vec<_> = recorder
+        .events()
+        .into_iter()
+        .map(|(level, message)| {
+            assert_eq!(level, Level::DEBUG, "message: {message}");
+            message
+        })
+        .collect();
+    assert_eq!(
+        messages,
+        [
+            "created temporary file",
+            "persisted temporary file",
+            "created temporary file",
+            "removed temporary file on drop",
+        ]
+    );
+}
+
+#[test]
+fn test_dir_drop_failure() {
+    let recorder = Recorder::default();
+
+    let kept = subscriber::with_default(recorder.clone(), || {
+        let dir = Builder::new().tempdir().unwrap();
+        // Remove the directory out from under the `Utf8TempDir`, so that
+        // removing it on drop fails.
+        fs::remove_dir(dir.path()).unwrap();
+        drop(dir);
+
+        let dir = Utf8TempDir::new().unwrap();
+        dir.keep()
+    });
+    fs::remove_dir(kept).unwrap();
+
+    let events = recorder.events();
+    assert_eq!(events.len(), 4, "events: {events:?}");
+    assert_eq!(
+        events[0],
+        (Level::DEBUG, "created temporary dir".to_owned())
+    );
+    assert_eq!(
+        events[1],
+        (
+            Level::WARN,
+            "failed to remove temporary dir on drop".to_owned()
+        )
+    );
+    assert_eq!(events[3], (Level::DEBUG, "kept temporary dir".to_owned()));
+}